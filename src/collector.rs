@@ -9,7 +9,7 @@ mod router;
 pub use self::{
     pager::Pager,
     poller::Poller,
-    request::{PollEvent, Request},
+    request::{GovernanceEvent, PollEvent, ReferenceEvent, Request, StakingEvent, UpgradeEvent},
     response::Response,
     router::Router,
 };
@@ -102,6 +102,58 @@ impl Collector {
 
         Ok(Response::PollEvent)
     }
+
+    /// Handle incoming staking status from the poller
+    fn handle_staking_event(&mut self, event: StakingEvent) -> Result<Response, Error> {
+        match self.networks.get_mut(&event.network_id) {
+            Some(network) => network.handle_staking_event(event),
+            None => warn!(
+                "got staking status for unregistered network: {}",
+                event.network_id
+            ),
+        }
+
+        Ok(Response::PollEvent)
+    }
+
+    /// Handle incoming governance proposals from the poller
+    fn handle_governance_event(&mut self, event: GovernanceEvent) -> Result<Response, Error> {
+        match self.networks.get_mut(&event.network_id) {
+            Some(network) => network.handle_governance_event(event),
+            None => warn!(
+                "got proposals for unregistered network: {}",
+                event.network_id
+            ),
+        }
+
+        Ok(Response::PollEvent)
+    }
+
+    /// Handle incoming software upgrade plan from the poller
+    fn handle_upgrade_event(&mut self, event: UpgradeEvent) -> Result<Response, Error> {
+        match self.networks.get_mut(&event.network_id) {
+            Some(network) => network.handle_upgrade_event(event),
+            None => warn!(
+                "got upgrade plan for unregistered network: {}",
+                event.network_id
+            ),
+        }
+
+        Ok(Response::PollEvent)
+    }
+
+    /// Handle incoming reference height from the poller
+    fn handle_reference_event(&mut self, event: ReferenceEvent) -> Result<Response, Error> {
+        match self.networks.get_mut(&event.network_id) {
+            Some(network) => network.handle_reference_event(event),
+            None => warn!(
+                "got reference height for unregistered network: {}",
+                event.network_id
+            ),
+        }
+
+        Ok(Response::PollEvent)
+    }
 }
 
 impl Service<Request> for Collector {
@@ -119,6 +171,10 @@ impl Service<Request> for Collector {
             Request::NetworkState(id) => self.network_state(&id),
             Request::PagerEvents => self.get_pager_events(),
             Request::PollEvent(info) => self.handle_poll_event(info),
+            Request::StakingEvent(event) => self.handle_staking_event(event),
+            Request::GovernanceEvent(event) => self.handle_governance_event(event),
+            Request::UpgradeEvent(event) => self.handle_upgrade_event(event),
+            Request::ReferenceEvent(event) => self.handle_reference_event(event),
         };
 
         Box::pin(async { result })
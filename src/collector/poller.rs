@@ -1,9 +1,12 @@
 //! Collector poller
 
+mod abci;
+
 #[cfg(feature = "mintscan")]
 mod mintscan;
 
 mod ngexplorers;
+mod reference;
 
 use crate::{collector, config, prelude::*};
 use futures::future;
@@ -22,6 +25,12 @@ pub struct Poller {
     mintscan: Vec<mintscan::Poller>,
 
     ngexplorers: Vec<ngexplorers::Poller>,
+
+    /// Nodes to query on-chain state from
+    abci: Vec<abci::Poller>,
+
+    /// Reference RPC endpoints to compare node heights against
+    reference: Vec<reference::Poller>,
 }
 
 impl Poller {
@@ -47,11 +56,27 @@ impl Poller {
             .flat_map(ngexplorers::Poller::new)
             .collect();
 
+        let abci = config
+            .networks
+            .tendermint
+            .iter()
+            .filter_map(|network| abci::Poller::new(network).transpose())
+            .collect::<Result<_, _>>()?;
+
+        let reference = config
+            .networks
+            .tendermint
+            .iter()
+            .filter_map(|network| reference::Poller::new(network).transpose())
+            .collect::<Result<_, _>>()?;
+
         Ok(Self {
             poll_interval,
             #[cfg(feature = "mintscan")]
             mintscan,
             ngexplorers,
+            abci,
+            reference,
         })
     }
 
@@ -105,6 +130,22 @@ impl Poller {
         }
 
         future::join_all(ngexplorers_futures).await;
+
+        let mut abci_futures = vec![];
+
+        for abci_poller in &self.abci {
+            abci_futures.push(abci_poller.poll(collector.clone()));
+        }
+
+        future::join_all(abci_futures).await;
+
+        let mut reference_futures = vec![];
+
+        for reference_poller in &self.reference {
+            reference_futures.push(reference_poller.poll(collector.clone()));
+        }
+
+        future::join_all(reference_futures).await;
     }
 
     /// Are there any configured sources?
@@ -114,6 +155,6 @@ impl Poller {
             return true;
         }
 
-        false
+        !self.abci.is_empty() || !self.reference.is_empty()
     }
 }
@@ -0,0 +1,512 @@
+//! ABCI query poller: queries on-chain state from a node's application
+
+use crate::{
+    collector, config,
+    monitor::rpc,
+    network,
+    network::tendermint::{BondStatus, Proposal, UpgradePlan, ValidatorStatus, VoteOption},
+    prelude::*,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use cosmos_sdk_proto::cosmos::{
+    crypto::ed25519, gov::v1beta1 as gov, slashing::v1beta1 as slashing,
+    staking::v1beta1 as staking, upgrade::v1beta1 as upgrade,
+};
+use prost::Message as _;
+use subtle_encoding::bech32;
+use tendermint::{account, chain, PublicKey};
+use tendermint_rpc::{endpoint::abci_query::AbciQuery, Client, HttpClient, Paging};
+use tower::{util::ServiceExt, Service};
+
+/// Number of recent blocks used to estimate the block time
+const BLOCK_TIME_SAMPLE: u64 = 100;
+
+/// Type URL of Ed25519 consensus keys
+const ED25519_PUBKEY_TYPE_URL: &str = "/cosmos.crypto.ed25519.PubKey";
+
+/// Code of the Cosmos SDK's `ErrInvalidRequest`, which the gov module's gRPC
+/// `InvalidArgument` error for a voter who hasn't voted is mapped to
+const SDK_INVALID_REQUEST: u32 = 18;
+
+/// ABCI query poller
+pub struct Poller {
+    /// RPC URL of the node to query
+    rpc_url: String,
+
+    /// RPC client
+    client: HttpClient,
+
+    /// Tendermint chain ID
+    chain_id: chain::Id,
+
+    /// Validator operator address (if configured)
+    validator_addr: Option<String>,
+}
+
+impl Poller {
+    /// Name of this poller source
+    pub const SOURCE_NAME: &'static str = "abci";
+
+    /// Create a new ABCI query poller for the given Tendermint network, if it
+    /// has an RPC URL configured.
+    pub fn new(config: &config::network::tendermint::Config) -> Result<Option<Self>, Error> {
+        let rpc_url = match &config.rpc_url {
+            Some(rpc_url) => rpc_url.clone(),
+            None => return Ok(None),
+        };
+
+        let client = HttpClient::new(rpc_url.as_str()).map_err(|e| {
+            format_err!(
+                ErrorKind::ConfigError,
+                "invalid rpc_url for {}: {} ({})",
+                &config.chain_id,
+                &rpc_url,
+                e
+            )
+        })?;
+
+        Ok(Some(Self {
+            rpc_url,
+            client,
+            chain_id: config.chain_id.clone(),
+            validator_addr: config.validator_addr.clone(),
+        }))
+    }
+
+    /// Poll the node's application for on-chain state
+    pub async fn poll<S>(&self, mut collector: S)
+    where
+        S: Service<collector::Request, Response = collector::Response, Error = BoxError>
+            + Send
+            + Clone
+            + 'static,
+    {
+        match self.upgrade_plan().await {
+            Ok(plan) => {
+                let event = collector::UpgradeEvent {
+                    source: Self::SOURCE_NAME,
+                    network_id: network::Id::from(&self.chain_id),
+                    plan,
+                };
+
+                send(&mut collector, event.into()).await;
+            }
+            Err(err) => warn!(
+                "[{}] can't query upgrade plan from {}: {}",
+                &self.chain_id, &self.rpc_url, err
+            ),
+        }
+
+        let addr = match &self.validator_addr {
+            Some(addr) => addr,
+            None => return,
+        };
+
+        match self.validator_status(addr).await {
+            Ok(Some(validator)) => {
+                let event = collector::StakingEvent {
+                    source: Self::SOURCE_NAME,
+                    network_id: network::Id::from(&self.chain_id),
+                    validator,
+                };
+
+                send(&mut collector, event.into()).await;
+            }
+            Ok(None) => debug!(
+                "[{}] {} is not a validator operator address; skipping staking status",
+                &self.chain_id, addr
+            ),
+            Err(err) => warn!(
+                "[{}] can't query staking status for {} from {}: {}",
+                &self.chain_id, addr, &self.rpc_url, err
+            ),
+        }
+
+        match self.proposals(addr).await {
+            Ok(Some(proposals)) => {
+                let event = collector::GovernanceEvent {
+                    source: Self::SOURCE_NAME,
+                    network_id: network::Id::from(&self.chain_id),
+                    proposals,
+                };
+
+                send(&mut collector, event.into()).await;
+            }
+            Ok(None) => (),
+            Err(err) => warn!(
+                "[{}] can't query governance proposals from {}: {}",
+                &self.chain_id, &self.rpc_url, err
+            ),
+        }
+    }
+
+    /// Query the currently scheduled software upgrade (if any), estimating
+    /// when it will happen from recent block times
+    pub async fn upgrade_plan(&self) -> Result<Option<UpgradePlan>, Error> {
+        let plan = self
+            .query_opt::<_, upgrade::QueryCurrentPlanResponse>(
+                "/cosmos.upgrade.v1beta1.Query/CurrentPlan",
+                upgrade::QueryCurrentPlanRequest {},
+            )
+            .await?
+            .and_then(|response| response.plan);
+
+        let plan = match plan {
+            Some(plan) => plan,
+            None => return Ok(None),
+        };
+
+        let height = plan.height as u64;
+
+        Ok(Some(UpgradePlan {
+            estimated_time: self.estimate_time(height).await?,
+            name: plan.name,
+            height,
+            info: plan.info,
+        }))
+    }
+
+    /// Estimate when the given height will be reached from recent block
+    /// times (`None` if it already has been)
+    async fn estimate_time(&self, height: u64) -> Result<Option<DateTime<Utc>>, Error> {
+        let latest = self.client.latest_block().await?.block.header;
+        let latest_height = latest.height.value();
+
+        if height <= latest_height || latest_height <= 1 {
+            return Ok(None);
+        }
+
+        let sample_height = latest_height.saturating_sub(BLOCK_TIME_SAMPLE).max(1);
+        let sample = self.client.block(sample_height as u32).await?.block.header;
+
+        let elapsed = match latest.time.duration_since(sample.time) {
+            Ok(elapsed) => elapsed,
+            Err(_) => return Ok(None),
+        };
+
+        let block_time = elapsed / (latest_height - sample_height) as u32;
+        let remaining = block_time * (height - latest_height) as u32;
+
+        Ok(chrono::Duration::from_std(remaining)
+            .ok()
+            .map(|remaining| Utc::now() + remaining))
+    }
+
+    /// Query the proposals which are in their voting period, along with how
+    /// the validator with the given operator address voted on them.
+    ///
+    /// Returns `None` if the address isn't a validator operator address.
+    pub async fn proposals(&self, addr: &str) -> Result<Option<Vec<Proposal>>, Error> {
+        let voter = match account_address(addr) {
+            Some(voter) => voter,
+            None => return Ok(None),
+        };
+
+        let response: gov::QueryProposalsResponse = self
+            .query(
+                "/cosmos.gov.v1beta1.Query/Proposals",
+                gov::QueryProposalsRequest {
+                    proposal_status: gov::ProposalStatus::VotingPeriod as i32,
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        let mut proposals = vec![];
+
+        for proposal in response.proposals {
+            // All proposal content types have the title as their first field
+            let title = proposal
+                .content
+                .as_ref()
+                .and_then(|content| gov::TextProposal::decode(content.value.as_slice()).ok())
+                .map(|content| content.title)
+                .unwrap_or_default();
+
+            let voting_end_time = proposal
+                .voting_end_time
+                .as_ref()
+                .and_then(|t| Utc.timestamp_opt(t.seconds, t.nanos as u32).single());
+
+            proposals.push(Proposal {
+                id: proposal.proposal_id,
+                title,
+                voting_end_time,
+                vote: self.vote(proposal.proposal_id, &voter).await?,
+            });
+        }
+
+        Ok(Some(proposals))
+    }
+
+    /// Query how the given voter voted on a proposal (`None` if they haven't
+    /// voted on it yet)
+    async fn vote(&self, proposal_id: u64, voter: &str) -> Result<Option<VoteOption>, Error> {
+        let path = "/cosmos.gov.v1beta1.Query/Vote";
+        let request = gov::QueryVoteRequest {
+            proposal_id,
+            voter: voter.to_owned(),
+        };
+
+        let response = self
+            .client
+            .abci_query(Some(path.to_owned()), request.encode_to_vec(), None, false)
+            .await?;
+
+        if is_missing_vote(&response) {
+            return Ok(None);
+        }
+
+        Ok(
+            rpc::decode_abci_response::<gov::QueryVoteResponse>(path, response)?
+                .and_then(|response| response.vote)
+                .map(|vote| {
+                    // Weighted votes are reported by their first option
+                    vote_option(
+                        vote.options
+                            .first()
+                            .map(|weighted| weighted.option)
+                            .unwrap_or_default(),
+                    )
+                }),
+        )
+    }
+
+    /// Query the staking and slashing status of the validator with the given
+    /// operator address (e.g. `cosmosvaloper1...`).
+    ///
+    /// Returns `None` if the address isn't a validator operator address.
+    pub async fn validator_status(&self, addr: &str) -> Result<Option<ValidatorStatus>, Error> {
+        let hrp = match addr.rsplit_once('1') {
+            Some((hrp, _)) if hrp.ends_with("valoper") => hrp,
+            _ => return Ok(None),
+        };
+
+        let response: staking::QueryValidatorResponse = self
+            .query(
+                "/cosmos.staking.v1beta1.Query/Validator",
+                staking::QueryValidatorRequest {
+                    validator_addr: addr.to_owned(),
+                },
+            )
+            .await?;
+
+        let validator = response
+            .validator
+            .ok_or_else(|| format_err!(ErrorKind::RpcError, "unknown validator: {}", addr))?;
+
+        let consensus_key = validator
+            .consensus_pubkey
+            .as_ref()
+            .filter(|pubkey| pubkey.type_url == ED25519_PUBKEY_TYPE_URL)
+            .and_then(|pubkey| ed25519::PubKey::decode(pubkey.value.as_slice()).ok())
+            .and_then(|pubkey| PublicKey::from_raw_ed25519(&pubkey.key))
+            .map(account::Id::from);
+
+        let voting_power = match consensus_key {
+            Some(consensus_key) => self.voting_power(consensus_key).await?,
+            None => 0,
+        };
+
+        let consensus_address = consensus_key.map(|consensus_key| {
+            let valcons_hrp = format!("{}valcons", hrp.trim_end_matches("valoper"));
+            bech32::encode(valcons_hrp, consensus_key.as_bytes())
+        });
+
+        let signing_info = match &consensus_address {
+            Some(consensus_address) => {
+                let response: slashing::QuerySigningInfoResponse = self
+                    .query(
+                        "/cosmos.slashing.v1beta1.Query/SigningInfo",
+                        slashing::QuerySigningInfoRequest {
+                            cons_address: consensus_address.clone(),
+                        },
+                    )
+                    .await?;
+
+                response.val_signing_info
+            }
+            None => None,
+        };
+
+        let commission_rate = validator
+            .commission
+            .and_then(|commission| commission.commission_rates)
+            .and_then(|rates| parse_dec(&rates.rate));
+
+        Ok(Some(ValidatorStatus {
+            operator_address: validator.operator_address,
+            consensus_address,
+            status: bond_status(validator.status),
+            jailed: validator.jailed,
+            tombstoned: signing_info.as_ref().map(|info| info.tombstoned),
+            voting_power,
+            tokens: validator.tokens,
+            commission_rate,
+            missed_blocks_counter: signing_info.map(|info| info.missed_blocks_counter),
+        }))
+    }
+
+    /// Get the voting power of the validator with the given consensus
+    /// address in the latest validator set
+    async fn voting_power(&self, consensus_address: account::Id) -> Result<u64, Error> {
+        let height = self.client.status().await?.sync_info.latest_block_height;
+
+        Ok(self
+            .client
+            .validators(height, Paging::All)
+            .await?
+            .validators
+            .iter()
+            .find(|validator| validator.address == consensus_address)
+            .map(|validator| validator.power.value())
+            .unwrap_or_default())
+    }
+
+    /// Perform an ABCI query for a gRPC method of the application
+    async fn query<Req, Res>(&self, path: &str, request: Req) -> Result<Res, Error>
+    where
+        Req: prost::Message,
+        Res: prost::Message + Default,
+    {
+        self.query_opt(path, request)
+            .await?
+            .ok_or_else(|| format_err!(ErrorKind::RpcError, "{} failed: not found", path).into())
+    }
+
+    /// Perform an ABCI query for a gRPC method of the application, returning
+    /// `None` if the application reports the queried item doesn't exist
+    async fn query_opt<Req, Res>(&self, path: &str, request: Req) -> Result<Option<Res>, Error>
+    where
+        Req: prost::Message,
+        Res: prost::Message + Default,
+    {
+        rpc::abci_query(&self.client, path, request).await
+    }
+}
+
+/// Send a request to the collector
+async fn send<S>(collector: &mut S, request: collector::Request)
+where
+    S: Service<collector::Request, Response = collector::Response, Error = BoxError>
+        + Send
+        + Clone
+        + 'static,
+{
+    collector
+        .ready()
+        .await
+        .expect("collector not ready")
+        .call(request)
+        .await
+        .expect("error sending ABCI query results");
+}
+
+/// Get the account address of a validator from its operator address, e.g.
+/// `cosmosvaloper1...` becomes `cosmos1...`
+fn account_address(operator_addr: &str) -> Option<String> {
+    let (hrp, data) = bech32::decode(operator_addr).ok()?;
+    let account_hrp = hrp.strip_suffix("valoper")?;
+    Some(bech32::encode(account_hrp, data))
+}
+
+/// Is the given response to a gov `Vote` query the error the gov module
+/// returns when the voter hasn't voted on the proposal?
+fn is_missing_vote(response: &AbciQuery) -> bool {
+    response.codespace == rpc::SDK_CODESPACE
+        && response.code.value() == SDK_INVALID_REQUEST
+        && response.log.contains("not found")
+}
+
+/// Convert a protobuf vote option
+fn vote_option(option: i32) -> VoteOption {
+    match gov::VoteOption::from_i32(option) {
+        Some(gov::VoteOption::Yes) => VoteOption::Yes,
+        Some(gov::VoteOption::Abstain) => VoteOption::Abstain,
+        Some(gov::VoteOption::No) => VoteOption::No,
+        Some(gov::VoteOption::NoWithVeto) => VoteOption::NoWithVeto,
+        _ => VoteOption::Unspecified,
+    }
+}
+
+/// Convert a protobuf bond status
+fn bond_status(status: i32) -> BondStatus {
+    match staking::BondStatus::from_i32(status) {
+        Some(staking::BondStatus::Bonded) => BondStatus::Bonded,
+        Some(staking::BondStatus::Unbonding) => BondStatus::Unbonding,
+        Some(staking::BondStatus::Unbonded) => BondStatus::Unbonded,
+        _ => BondStatus::Unspecified,
+    }
+}
+
+/// Format a protobuf-encoded `sdk.Dec` (an integer scaled by 10^18) as a
+/// decimal string, e.g. `50000000000000000` becomes `0.05`
+fn parse_dec(s: &str) -> Option<String> {
+    const PRECISION: usize = 18;
+
+    if s.contains('.') {
+        return Some(s.to_owned());
+    }
+
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let digits = format!("{:0>width$}", s, width = PRECISION + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - PRECISION);
+    let integer = integer.trim_start_matches('0');
+    let fraction = fraction.trim_end_matches('0');
+
+    Some(match (integer.is_empty(), fraction.is_empty()) {
+        (true, true) => "0".to_owned(),
+        (true, false) => format!("0.{}", fraction),
+        (false, true) => integer.to_owned(),
+        (false, false) => format!("{}.{}", integer, fraction),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_missing_vote, parse_dec};
+    use tendermint::abci::Code;
+    use tendermint_rpc::endpoint::abci_query::AbciQuery;
+
+    #[test]
+    fn parses_dec() {
+        assert_eq!(parse_dec("50000000000000000").unwrap(), "0.05");
+        assert_eq!(parse_dec("1000000000000000000").unwrap(), "1");
+        assert_eq!(parse_dec("1500000000000000000").unwrap(), "1.5");
+        assert_eq!(parse_dec("0").unwrap(), "0");
+        assert_eq!(
+            parse_dec("0.100000000000000000").unwrap(),
+            "0.100000000000000000"
+        );
+        assert!(parse_dec("nope").is_none());
+    }
+
+    #[test]
+    fn detects_missing_vote() {
+        let unvoted = AbciQuery {
+            code: Code::from(18),
+            log: "rpc error: code = InvalidArgument desc = voter: cosmos1abc not found for \
+                  proposal: 42: invalid request"
+                .to_owned(),
+            codespace: "sdk".to_owned(),
+            ..Default::default()
+        };
+        assert!(is_missing_vote(&unvoted));
+
+        let invalid_voter = AbciQuery {
+            log: "rpc error: code = InvalidArgument desc = invalid voter address".to_owned(),
+            ..unvoted.clone()
+        };
+        assert!(!is_missing_vote(&invalid_voter));
+
+        let voted = AbciQuery {
+            code: Code::Ok,
+            log: String::new(),
+            ..unvoted
+        };
+        assert!(!is_missing_vote(&voted));
+    }
+}
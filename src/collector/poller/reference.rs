@@ -0,0 +1,124 @@
+//! Reference RPC poller: tracks the best height of trusted RPC endpoints
+
+use crate::{collector, config, network, network::tendermint::ReferenceHeight, prelude::*};
+use tendermint::chain;
+use tendermint_rpc::{Client, HttpClient};
+use tower::{util::ServiceExt, Service};
+
+/// Reference RPC poller
+pub struct Poller {
+    /// Tendermint chain ID
+    chain_id: chain::Id,
+
+    /// Reference RPC endpoints and their clients
+    endpoints: Vec<(String, HttpClient)>,
+}
+
+impl Poller {
+    /// Name of this poller source
+    pub const SOURCE_NAME: &'static str = "reference_rpcs";
+
+    /// Create a new reference RPC poller for the given Tendermint network, if
+    /// it has any reference RPC endpoints configured.
+    pub fn new(config: &config::network::tendermint::Config) -> Result<Option<Self>, Error> {
+        if config.reference_rpcs.is_empty() {
+            return Ok(None);
+        }
+
+        let endpoints = config
+            .reference_rpcs
+            .iter()
+            .map(|url| {
+                let client = HttpClient::new(url.as_str()).map_err(|e| {
+                    format_err!(
+                        ErrorKind::ConfigError,
+                        "invalid reference RPC for {}: {} ({})",
+                        &config.chain_id,
+                        url,
+                        e
+                    )
+                })?;
+
+                Ok((url.clone(), client))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Some(Self {
+            chain_id: config.chain_id.clone(),
+            endpoints,
+        }))
+    }
+
+    /// Poll the reference endpoints and report the best height among them
+    pub async fn poll<S>(&self, mut collector: S)
+    where
+        S: Service<collector::Request, Response = collector::Response, Error = BoxError>
+            + Send
+            + Clone
+            + 'static,
+    {
+        let mut best: Option<ReferenceHeight> = None;
+
+        for (url, client) in &self.endpoints {
+            let status = match client.status().await {
+                Ok(status) => status,
+                Err(err) => {
+                    warn!("[{}] error polling {}: {}", &self.chain_id, url, err);
+                    continue;
+                }
+            };
+
+            if status.node_info.network != self.chain_id {
+                warn!(
+                    "[{}] ignoring {}: wrong chain ID ({})",
+                    &self.chain_id, url, &status.node_info.network
+                );
+                continue;
+            }
+
+            let sync_info = status.sync_info;
+
+            if sync_info.catching_up {
+                debug!("[{}] ignoring {}: catching up", &self.chain_id, url);
+                continue;
+            }
+
+            let height = sync_info.latest_block_height.value();
+
+            if best
+                .as_ref()
+                .map(|best| height > best.height)
+                .unwrap_or(true)
+            {
+                best = Some(ReferenceHeight {
+                    endpoint: url.clone(),
+                    height,
+                    time: sync_info.latest_block_time,
+                });
+            }
+        }
+
+        let reference = match best {
+            Some(reference) => reference,
+            None => {
+                warn!("[{}] no reference RPC endpoints available", &self.chain_id);
+                return;
+            }
+        };
+
+        collector
+            .ready()
+            .await
+            .expect("collector not ready")
+            .call(
+                collector::ReferenceEvent {
+                    source: Self::SOURCE_NAME,
+                    network_id: network::Id::from(&self.chain_id),
+                    reference,
+                }
+                .into(),
+            )
+            .await
+            .expect("error sending reference height");
+    }
+}
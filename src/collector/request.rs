@@ -1,6 +1,9 @@
 //! Requests to the collector
 
-use crate::{message, network};
+use crate::{
+    message, network,
+    network::tendermint::{Proposal, ReferenceHeight, UpgradePlan, ValidatorStatus},
+};
 
 /// Block height type
 pub type BlockHeight = u64;
@@ -19,6 +22,18 @@ pub enum Request {
 
     /// Report information obtained from an external poller.
     PollEvent(PollEvent),
+
+    /// Report the staking status of a validator obtained via ABCI queries.
+    StakingEvent(StakingEvent),
+
+    /// Report governance proposals obtained via ABCI queries.
+    GovernanceEvent(GovernanceEvent),
+
+    /// Report the scheduled software upgrade obtained via ABCI queries.
+    UpgradeEvent(UpgradeEvent),
+
+    /// Report the best height of the reference RPC endpoints.
+    ReferenceEvent(ReferenceEvent),
 }
 
 impl From<message::Envelope> for Request {
@@ -33,6 +48,30 @@ impl From<PollEvent> for Request {
     }
 }
 
+impl From<StakingEvent> for Request {
+    fn from(event: StakingEvent) -> Request {
+        Request::StakingEvent(event)
+    }
+}
+
+impl From<GovernanceEvent> for Request {
+    fn from(event: GovernanceEvent) -> Request {
+        Request::GovernanceEvent(event)
+    }
+}
+
+impl From<UpgradeEvent> for Request {
+    fn from(event: UpgradeEvent) -> Request {
+        Request::UpgradeEvent(event)
+    }
+}
+
+impl From<ReferenceEvent> for Request {
+    fn from(event: ReferenceEvent) -> Request {
+        Request::ReferenceEvent(event)
+    }
+}
+
 /// Information obtained from an external poller.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PollEvent {
@@ -49,3 +88,56 @@ pub struct PollEvent {
     /// Last block signed by the validator for this chain, if known.
     pub missed_blocks: Option<usize>,
 }
+
+/// Staking status of a validator obtained from an external poller.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StakingEvent {
+    /// Source the data was obtained from
+    pub source: &'static str,
+
+    /// Network ID the information is associated with.
+    pub network_id: network::Id,
+
+    /// Staking status of the validator.
+    pub validator: ValidatorStatus,
+}
+
+/// Governance proposals in their voting period obtained from an external
+/// poller.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GovernanceEvent {
+    /// Source the data was obtained from
+    pub source: &'static str,
+
+    /// Network ID the information is associated with.
+    pub network_id: network::Id,
+
+    /// Proposals in their voting period, and how the validator voted.
+    pub proposals: Vec<Proposal>,
+}
+
+/// Scheduled software upgrade obtained from an external poller.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpgradeEvent {
+    /// Source the data was obtained from
+    pub source: &'static str,
+
+    /// Network ID the information is associated with.
+    pub network_id: network::Id,
+
+    /// Scheduled upgrade (if any).
+    pub plan: Option<UpgradePlan>,
+}
+
+/// Best height of a network's reference RPC endpoints.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReferenceEvent {
+    /// Source the data was obtained from
+    pub source: &'static str,
+
+    /// Network ID the information is associated with.
+    pub network_id: network::Id,
+
+    /// Best height among the reference endpoints.
+    pub reference: ReferenceHeight,
+}
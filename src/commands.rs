@@ -1,8 +1,9 @@
 //! cosmon subcommands
 
 mod start;
+mod sync;
 
-use self::start::StartCommand;
+use self::{start::StartCommand, sync::SyncCommand};
 use crate::config::CosmonConfig;
 use abscissa_core::{Command, Configurable, Runnable};
 use clap::Parser;
@@ -17,6 +18,10 @@ pub enum CosmonCommand {
     /// The `start` subcommand
     #[clap()]
     Start(StartCommand),
+
+    /// The `sync` subcommand
+    #[clap()]
+    Sync(SyncCommand),
 }
 
 impl Configurable<CosmonConfig> for EntryPoint {
@@ -4,16 +4,19 @@ use crate::{
     application::APP,
     collector::{self, Collector},
     config,
-    monitor::Monitor,
+    monitor::{Monitor, Reporter},
     prelude::*,
 };
 use abscissa_core::{Command, Runnable};
 use clap::Parser;
 use futures::future;
-use std::process;
-use tokio::task::JoinHandle;
+use std::{process, time::Duration};
+use tokio::{sync::mpsc, task::JoinHandle, time};
 use tower::{Service, ServiceBuilder};
 
+/// Amount of time to wait before retrying to initialize a node's monitor
+const MONITOR_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
 /// `start` subcommand
 #[derive(Command, Debug, Parser)]
 pub struct StartCommand {}
@@ -24,9 +27,7 @@ impl Runnable for StartCommand {
         abscissa_tokio::run(&APP, async {
             let mut tasks = self.init_collector().await;
 
-            if let Some(monitor) = self.init_monitor().await {
-                tasks.push(monitor);
-            }
+            tasks.extend(self.init_monitors().await);
 
             future::join_all(tasks).await;
         })
@@ -136,19 +137,54 @@ impl StartCommand {
         })
     }
 
-    /// Initialize monitor (if configured)
-    async fn init_monitor(&self) -> Option<JoinHandle<()>> {
+    /// Initialize a monitor for each configured node and the reporter they
+    /// share (if configured)
+    async fn init_monitors(&self) -> Vec<JoinHandle<()>> {
+        let mut tasks = vec![];
+
         if let Some(config) = APP.config().agent.clone() {
-            let mut monitor = Monitor::new(&config).await.unwrap_or_else(|e| {
-                status_err!("couldn't initialize monitor: {}", e);
+            let nodes = config.nodes();
+
+            if nodes.is_empty() {
+                status_err!("no nodes configured for agent (use `node_home` or `[[agent.nodes]]`)");
+                process::exit(1);
+            }
+
+            let reporter = Reporter::new(&config).unwrap_or_else(|e| {
+                status_err!("couldn't initialize reporter: {}", e);
                 process::exit(1);
             });
 
-            Some(tokio::spawn(async move {
-                monitor.run().await;
-            }))
-        } else {
-            None
+            let (reporter_tx, reporter_rx) = mpsc::unbounded_channel();
+            tasks.push(tokio::spawn(reporter.run(reporter_rx)));
+
+            for node_config in nodes {
+                let config = config.clone();
+                let reporter_tx = reporter_tx.clone();
+
+                // A node which can't be reached yet shouldn't stop the others
+                // from being monitored, so keep retrying it in the background
+                tasks.push(tokio::spawn(async move {
+                    let monitor = loop {
+                        match Monitor::new(&config, &node_config, reporter_tx.clone()).await {
+                            Ok(monitor) => break monitor,
+                            Err(e) => {
+                                status_err!(
+                                    "couldn't initialize monitor for {}: {}; retrying in {:?}",
+                                    node_config.home.display(),
+                                    e,
+                                    MONITOR_RETRY_INTERVAL
+                                );
+                                time::sleep(MONITOR_RETRY_INTERVAL).await;
+                            }
+                        }
+                    };
+
+                    monitor.run().await;
+                }));
+            }
         }
+
+        tasks
     }
 }
@@ -0,0 +1,126 @@
+//! `sync` subcommand
+
+use crate::{application::APP, config, prelude::*, response};
+use abscissa_core::{Command, Runnable};
+use clap::Parser;
+use serde_json::Value;
+use std::process;
+
+/// `sync` subcommand: shows the catch-up progress of a network's nodes as
+/// reported by the collector
+#[derive(Command, Debug, Parser)]
+pub struct SyncCommand {
+    /// Network to show (i.e. its chain ID)
+    network: String,
+
+    /// URL of the collector (defaults to the configured collector)
+    #[clap(long)]
+    collector: Option<String>,
+}
+
+impl Runnable for SyncCommand {
+    /// Show the catch-up progress of a network's nodes.
+    fn run(&self) {
+        let collector = self.collector_url().unwrap_or_else(|| {
+            status_err!("no collector configured (use --collector)");
+            process::exit(1);
+        });
+
+        abscissa_tokio::run(&APP, async {
+            match self.fetch_nodes(&collector).await {
+                Ok(nodes) => {
+                    for node in &nodes {
+                        println!("{}", describe(node));
+                    }
+                }
+                Err(e) => {
+                    status_err!("couldn't get network state from {}: {}", collector, e);
+                    process::exit(1);
+                }
+            }
+        })
+        .expect("Tokio runtime crashed");
+    }
+}
+
+impl SyncCommand {
+    /// Get the URL of the collector, either as given or from the config
+    fn collector_url(&self) -> Option<String> {
+        if let Some(collector) = &self.collector {
+            return Some(collector.trim_end_matches('/').to_owned());
+        }
+
+        let config = APP.config();
+
+        if let Some(agent) = &config.agent {
+            let config::agent::CollectorAddr::Http(config::agent::HttpConfig { uri }) =
+                &agent.collector;
+            return Some(uri.to_string().trim_end_matches('/').to_owned());
+        }
+
+        config
+            .collector
+            .as_ref()
+            .map(|collector| format!("http://{}:{}", collector.listen.addr, collector.listen.port))
+    }
+
+    /// Get the nodes of the network from the collector's `GET /net/:id`
+    async fn fetch_nodes(&self, collector: &str) -> Result<Vec<Value>, Error> {
+        let url = format!("{}/net/{}", collector, self.network);
+
+        let wrapper = reqwest::get(&url)
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format_err!(ErrorKind::HttpError, "{}: {}", url, e))?
+            .json::<response::Wrapper<Value>>()
+            .await
+            .map_err(|e| format_err!(ErrorKind::HttpError, "{}: {}", url, e))?;
+
+        if let Some(error) = wrapper.error {
+            fail!(ErrorKind::HttpError, "{}: {}", url, error);
+        }
+
+        let nodes = wrapper
+            .result
+            .as_ref()
+            .and_then(|state| state["tendermint"]["nodes"].as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(nodes)
+    }
+}
+
+/// Describe the sync status of a node from its state
+fn describe(node: &Value) -> String {
+    let moniker = node["moniker"].as_str().unwrap_or("<unknown>");
+    let progress = &node["sync_progress"];
+
+    if progress.is_null() {
+        return match node["chain"]["latest_block_height"].as_str() {
+            Some(height) => format!("{}: synced at height {}", moniker, height),
+            None => format!("{}: no chain status reported", moniker),
+        };
+    }
+
+    let mut description = format!(
+        "{}: height {} of {} ({} blocks behind), {:.1} blocks/sec",
+        moniker,
+        progress["height"],
+        progress["target_height"],
+        progress["remaining_blocks"],
+        progress["blocks_per_sec"].as_f64().unwrap_or_default()
+    );
+
+    match progress["eta_secs"].as_u64() {
+        Some(eta_secs) => description.push_str(&format!(
+            ", ETA {}h{:02}m{:02}s",
+            eta_secs / 3600,
+            eta_secs / 60 % 60,
+            eta_secs % 60
+        )),
+        None => description.push_str(", not converging"),
+    }
+
+    description
+}
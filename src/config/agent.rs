@@ -2,26 +2,168 @@
 
 pub use tendermint_config::TendermintConfig;
 
-use crate::error::{Error, ErrorKind};
+use crate::{
+    error::{Error, ErrorKind},
+    monitor::{logs::Severity, metrics, scheduler},
+    net,
+    prelude::Map,
+};
 use iqhttp::Uri;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{num::NonZeroU64, path::PathBuf, time::Duration};
 
-/// Tendermint node-related config settings from `cosmon.toml`
+/// Log patterns used when none are configured: name, regex and severity
+pub const DEFAULT_LOG_PATTERNS: &[(&str, &str, Severity)] = &[
+    ("consensus_failure", "CONSENSUS FAILURE", Severity::Critical),
+    ("panic", "panic", Severity::Critical),
+    (
+        "app_hash_mismatch",
+        "wrong Block.Header.AppHash",
+        Severity::Critical,
+    ),
+    (
+        "signer_auth_failure",
+        "auth failure: secret conn failed",
+        Severity::Critical,
+    ),
+    ("dialing_failed", "dialing failed", Severity::Warning),
+];
+
+/// Monitoring agent config settings from `cosmon.toml`
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     /// Location of monitored Tendermint node's `--home` directory
-    pub node_home: PathBuf,
+    /// (shorthand for a single entry in `nodes`)
+    pub node_home: Option<PathBuf>,
+
+    /// RPC address of the node in `node_home` (defaults to `rpc.laddr`
+    /// from its `config.toml`)
+    pub rpc_addr: Option<net::Address>,
+
+    /// Log file of the node in `node_home`
+    pub log_file: Option<PathBuf>,
+
+    /// Tendermint nodes monitored by this agent
+    #[serde(default)]
+    pub nodes: Vec<NodeConfig>,
 
     /// Location of collector
     pub collector: CollectorAddr,
+
+    /// Directory where the agent keeps its state (defaults to `~/.cosmon`)
+    pub state_dir: Option<PathBuf>,
+
+    /// Outbox of message envelopes awaiting delivery to the collector
+    #[serde(default)]
+    pub outbox: OutboxConfig,
+
+    /// Interval at which checks report their full state (rather than only
+    /// changes) in seconds
+    pub full_report_interval_secs: Option<u64>,
+
+    /// Scheduling of individual checks, keyed by check name (e.g. `status`,
+    /// `signing`, `consensus`, `mempool`, `net_info`, `data`)
+    #[serde(default)]
+    pub checks: Map<String, CheckConfig>,
+
+    /// External (Nagios plugin style) commands run for every node
+    #[serde(default)]
+    pub commands: Vec<CommandConfig>,
+
+    /// Patterns to look for in node logs (defaults to [`DEFAULT_LOG_PATTERNS`])
+    pub log_patterns: Option<Vec<LogPatternConfig>>,
+
+    /// Prometheus metric families forwarded to the collector, without the
+    /// namespace prefix (defaults to [`metrics::DEFAULT_ALLOWLIST`])
+    pub metrics_allowlist: Option<Vec<String>>,
 }
 
 impl Config {
+    /// Get the configuration of all nodes monitored by this agent
+    pub fn nodes(&self) -> Vec<NodeConfig> {
+        let mut nodes = vec![];
+
+        if let Some(node_home) = &self.node_home {
+            nodes.push(NodeConfig {
+                home: node_home.clone(),
+                rpc_addr: self.rpc_addr.clone(),
+                log_file: self.log_file.clone(),
+            });
+        }
+
+        nodes.extend(self.nodes.iter().cloned());
+        nodes
+    }
+
+    /// Get the patterns to look for in node logs
+    pub fn log_patterns(&self) -> Vec<LogPatternConfig> {
+        match &self.log_patterns {
+            Some(log_patterns) => log_patterns.clone(),
+            None => DEFAULT_LOG_PATTERNS
+                .iter()
+                .map(|&(name, regex, severity)| LogPatternConfig {
+                    name: name.to_owned(),
+                    regex: regex.to_owned(),
+                    severity,
+                })
+                .collect(),
+        }
+    }
+
+    /// Get the Prometheus metric families forwarded to the collector
+    pub fn metrics_allowlist(&self) -> Vec<String> {
+        match &self.metrics_allowlist {
+            Some(allowlist) => allowlist.clone(),
+            None => metrics::DEFAULT_ALLOWLIST
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        }
+    }
+
+    /// Path to the agent's state directory
+    pub fn state_dir(&self) -> Result<PathBuf, Error> {
+        match &self.state_dir {
+            Some(state_dir) => Ok(state_dir.clone()),
+            None => home::home_dir()
+                .map(|home_dir| home_dir.join(".cosmon"))
+                .ok_or_else(|| {
+                    format_err!(
+                        ErrorKind::ConfigError,
+                        "couldn't determine home directory; please configure `state_dir`"
+                    )
+                    .into()
+                }),
+        }
+    }
+
+    /// Path to the agent's outbox directory
+    pub fn outbox_dir(&self) -> Result<PathBuf, Error> {
+        Ok(self.state_dir()?.join("outbox"))
+    }
+}
+
+/// Monitored Tendermint node config
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeConfig {
+    /// Location of the node's `--home` directory
+    pub home: PathBuf,
+
+    /// RPC address of the node (defaults to `rpc.laddr` from `config.toml`).
+    ///
+    /// Supports `tcp://` and `unix://` addresses.
+    pub rpc_addr: Option<net::Address>,
+
+    /// Log file of the node (e.g. where its stdout is redirected)
+    pub log_file: Option<PathBuf>,
+}
+
+impl NodeConfig {
     /// Path to the node's configuration directory
     pub fn config_dir(&self) -> PathBuf {
-        self.node_home.join("config")
+        self.home.join("config")
     }
 
     /// Path to the node's `config.toml` file
@@ -29,6 +171,51 @@ impl Config {
         self.config_dir().join("config.toml")
     }
 
+    /// Path to the node's `app.toml` file
+    pub fn app_toml_path(&self) -> PathBuf {
+        self.config_dir().join("app.toml")
+    }
+
+    /// Get the RPC address of the node, falling back to `rpc.laddr` from the
+    /// given `config.toml` if one hasn't been configured explicitly.
+    ///
+    /// Wildcard listen addresses are mapped to the loopback interface.
+    pub fn rpc_addr(&self, tendermint_config: &TendermintConfig) -> Result<net::Address, Error> {
+        if let Some(rpc_addr) = &self.rpc_addr {
+            return Ok(rpc_addr.clone());
+        }
+
+        let laddr = tendermint_config.rpc.laddr.to_string();
+
+        match laddr.parse::<net::Address>() {
+            Ok(net::Address::Tcp {
+                peer_id,
+                host,
+                port,
+            }) => {
+                let host = match host.as_str() {
+                    "0.0.0.0" => "127.0.0.1".to_owned(),
+                    "[::]" => "[::1]".to_owned(),
+                    _ => host,
+                };
+
+                Ok(net::Address::Tcp {
+                    peer_id,
+                    host,
+                    port,
+                })
+            }
+            Ok(addr) => Ok(addr),
+            Err(e) => Err(format_err!(
+                ErrorKind::ConfigError,
+                "invalid rpc.laddr in {}: {}",
+                self.config_toml_path().display(),
+                e
+            )
+            .into()),
+        }
+    }
+
     /// Load `TendermintConfig` using this node configuration
     pub fn load_tendermint_config(&self) -> Result<TendermintConfig, Error> {
         Ok(TendermintConfig::load_toml_file(&self.config_toml_path())
@@ -36,6 +223,104 @@ impl Config {
     }
 }
 
+/// Check scheduling config.
+///
+/// Unset values fall back to the defaults of the individual checks.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CheckConfig {
+    /// Interval at which the check is run in seconds (must be nonzero)
+    pub interval_secs: Option<NonZeroU64>,
+
+    /// Amount of time a single run of the check may take before it's
+    /// considered failed in seconds (must be nonzero)
+    pub timeout_secs: Option<NonZeroU64>,
+}
+
+impl CheckConfig {
+    /// Interval at which the check is run
+    pub fn interval(&self, default: Duration) -> Duration {
+        self.interval_secs
+            .map(|secs| Duration::from_secs(secs.get()))
+            .unwrap_or(default)
+    }
+
+    /// Amount of time a single run of the check may take
+    pub fn timeout(&self, default: Duration) -> Duration {
+        self.timeout_secs
+            .map(|secs| Duration::from_secs(secs.get()))
+            .unwrap_or(default)
+    }
+}
+
+/// External command config
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CommandConfig {
+    /// Name of the command (used when reporting and alerting)
+    pub name: String,
+
+    /// Program to run followed by its arguments
+    pub command: Vec<String>,
+
+    /// Interval at which the command is run in seconds (must be nonzero)
+    pub interval_secs: Option<NonZeroU64>,
+
+    /// Amount of time the command may take before it's killed and reported
+    /// as critical in seconds (must be nonzero)
+    pub timeout_secs: Option<NonZeroU64>,
+}
+
+impl CommandConfig {
+    /// Scheduling config for this command.
+    ///
+    /// Commands enforce their own timeout so they can report it as their
+    /// state, which is why the scheduler gives them an extra second.
+    pub fn schedule(&self) -> CheckConfig {
+        CheckConfig {
+            interval_secs: self.interval_secs,
+            timeout_secs: NonZeroU64::new(self.timeout().as_secs() + 1),
+        }
+    }
+
+    /// Amount of time the command may take before it's killed
+    pub fn timeout(&self) -> Duration {
+        self.timeout_secs
+            .map(|secs| Duration::from_secs(secs.get()))
+            .unwrap_or(scheduler::DEFAULT_TIMEOUT)
+    }
+}
+
+/// Log pattern config
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LogPatternConfig {
+    /// Name of the pattern (used when reporting and alerting)
+    pub name: String,
+
+    /// Regex to match log lines against
+    pub regex: String,
+
+    /// Severity of matching log lines
+    pub severity: Severity,
+}
+
+/// Outbox config
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutboxConfig {
+    /// Maximum number of undelivered envelopes to keep (oldest are evicted)
+    pub max_entries: usize,
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: 10_000,
+        }
+    }
+}
+
 /// Collector config
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -1,5 +1,8 @@
 //! Tendermint network configuration.
 
+pub mod thresholds;
+
+use crate::prelude::Map;
 use serde::{Deserialize, Serialize};
 use tendermint::chain;
 
@@ -14,12 +17,36 @@ pub struct Config {
     // TODO(tarcieri): proper address type
     pub validator_addr: Option<String>,
 
+    /// RPC endpoint of a node in this network used to query on-chain state
+    /// (e.g. `http://127.0.0.1:26657`).
+    pub rpc_url: Option<String>,
+
+    /// Trusted RPC endpoints (e.g. public nodes) whose best height nodes'
+    /// heights are compared against.
+    #[serde(default)]
+    pub reference_rpcs: Vec<String>,
+
     /// Mintscan API endpoint.
     #[cfg(feature = "mintscan")]
     pub mintscan: Option<MintscanConfig>,
 
     /// Explorers Guru API endpoint.
     pub ngexplorers: Option<NgExplorersConfig>,
+
+    /// Are validator nodes in this network supposed to use a remote signer
+    /// (e.g. a KMS) rather than a consensus key stored on disk?
+    #[serde(default)]
+    pub remote_signer: bool,
+
+    /// Expected values of node settings, keyed by setting (e.g.
+    /// `app.pruning` or `config.p2p.pex`). Nodes whose settings diverge
+    /// from these are alerted on.
+    #[serde(default)]
+    pub config_baseline: Map<String, String>,
+
+    /// Alerting thresholds.
+    #[serde(default)]
+    pub thresholds: thresholds::Config,
 }
 
 /// Mintscan configuration.
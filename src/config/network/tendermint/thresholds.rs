@@ -0,0 +1,100 @@
+//! Alerting thresholds for Tendermint networks.
+
+use serde::{Deserialize, Serialize};
+
+/// Alerting thresholds for a Tendermint network.
+///
+/// Any thresholds which are not explicitly configured use their defaults.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Alert when a node's disk is estimated to fill up within this many
+    /// seconds at its current growth rate.
+    pub disk_full_horizon_secs: u64,
+
+    /// Number of recent blocks in which the validator's signatures are
+    /// tracked.
+    pub missed_blocks_window: usize,
+
+    /// Alert when the validator misses more than this many blocks within
+    /// the missed blocks window.
+    pub missed_blocks_threshold: usize,
+
+    /// Alert when a node has been stuck at the same height for longer than
+    /// this many seconds.
+    pub consensus_stall_secs: u64,
+
+    /// Alert when a node's agent hasn't reported for longer than this many
+    /// seconds.
+    pub agent_missing_secs: u64,
+
+    /// Alert when a node's consensus round climbs beyond this round.
+    pub consensus_max_round: u32,
+
+    /// Fraction of a node's configured mempool capacity (`mempool.size` or
+    /// `mempool.max_txs_bytes`) above which its mempool is considered full.
+    pub mempool_fill_ratio: f64,
+
+    /// Alert when a node's mempool stays full for longer than this many
+    /// seconds.
+    pub mempool_fill_secs: u64,
+
+    /// Alert when a node's validator key has signed nothing for this many
+    /// blocks while the chain has kept progressing.
+    pub signing_lag_blocks: u64,
+
+    /// Alert when two nodes advance the signing state of the same validator
+    /// key within this many seconds of each other (double-sign risk).
+    pub double_sign_window_secs: u64,
+
+    /// Alert when a persistent peer has been disconnected for longer than
+    /// this many seconds.
+    pub peer_disconnect_secs: u64,
+
+    /// Alert when a private peer (e.g. a sentry or validator behind one)
+    /// has been disconnected for longer than this many seconds.
+    pub private_peer_disconnect_secs: u64,
+
+    /// Remind the validator to vote on governance proposals which end within
+    /// this many seconds.
+    pub gov_vote_reminder_secs: u64,
+
+    /// Number of recent blocks whose intervals are tracked.
+    pub block_time_window: usize,
+
+    /// Alert when the mean of the most recent block intervals exceeds the
+    /// median interval by this factor.
+    pub block_time_slowdown_ratio: f64,
+
+    /// Alert when a node is more than this many blocks behind the best
+    /// height of the reference RPC endpoints.
+    pub height_lag_blocks: u64,
+
+    /// Alert when a node's latest block is more than this many seconds
+    /// older than that of the reference RPC endpoints.
+    pub height_lag_secs: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            disk_full_horizon_secs: 24 * 60 * 60,
+            missed_blocks_window: 100,
+            missed_blocks_threshold: 10,
+            consensus_stall_secs: 120,
+            agent_missing_secs: 300,
+            consensus_max_round: 3,
+            mempool_fill_ratio: 0.8,
+            mempool_fill_secs: 300,
+            signing_lag_blocks: 10,
+            double_sign_window_secs: 60,
+            peer_disconnect_secs: 300,
+            private_peer_disconnect_secs: 60,
+            gov_vote_reminder_secs: 2 * 24 * 60 * 60,
+            block_time_window: 1000,
+            block_time_slowdown_ratio: 2.0,
+            height_lag_blocks: 10,
+            height_lag_secs: 60,
+        }
+    }
+}
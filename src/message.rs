@@ -1,6 +1,21 @@
 //! Message types (sent to collector)
 
-use crate::monitor::{net_info::Peer, status::ChainStatus};
+use crate::monitor::{
+    command::CommandStatus,
+    consensus::ConsensusState,
+    data::DataStatus,
+    keys::KeyAudit,
+    logs::LogEvent,
+    mempool::MempoolStatus,
+    metrics::Metric,
+    net_info::{AnomalousPeer, Peer},
+    priv_validator::PrivValidatorState,
+    scheduler::CheckStatus,
+    settings::NodeSettings,
+    signing::Signature,
+    status::ChainStatus,
+    upgrade::UpgradeReadiness,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tendermint::{chain, node};
@@ -23,6 +38,58 @@ pub enum Message {
     /// Peer connections
     #[serde(rename = "peers")]
     Peers(Vec<Peer>),
+
+    /// Peers which couldn't be processed
+    #[serde(rename = "anomalous_peers")]
+    AnomalousPeers(Vec<AnomalousPeer>),
+
+    /// Database directory status
+    #[serde(rename = "data")]
+    Data(Box<DataStatus>),
+
+    /// Signature of the local validator on a block
+    #[serde(rename = "signing")]
+    Signing(Signature),
+
+    /// Consensus state of a node
+    #[serde(rename = "consensus")]
+    Consensus(Box<ConsensusState>),
+
+    /// Mempool status of a node
+    #[serde(rename = "mempool")]
+    Mempool(MempoolStatus),
+
+    /// Status of a check which has failed or recovered
+    #[serde(rename = "check")]
+    Check(CheckStatus),
+
+    /// Result of running an external command
+    #[serde(rename = "command")]
+    Command(CommandStatus),
+
+    /// Node log lines matching a pattern
+    #[serde(rename = "log_event")]
+    LogEvent(LogEvent),
+
+    /// Last height/round/step signed by a node's validator key
+    #[serde(rename = "priv_validator")]
+    PrivValidator(PrivValidatorState),
+
+    /// Key material audit of a node
+    #[serde(rename = "keys")]
+    Keys(KeyAudit),
+
+    /// Configuration fingerprint and key settings of a node
+    #[serde(rename = "settings")]
+    Settings(NodeSettings),
+
+    /// Allowlisted Prometheus metrics of a node
+    #[serde(rename = "metrics")]
+    Metrics(Vec<Metric>),
+
+    /// Readiness of a node for the scheduled software upgrade
+    #[serde(rename = "upgrade")]
+    Upgrade(UpgradeReadiness),
 }
 
 impl From<ChainStatus> for Message {
@@ -49,6 +116,84 @@ impl From<Vec<Peer>> for Message {
     }
 }
 
+impl From<Vec<AnomalousPeer>> for Message {
+    fn from(anomalous_peers: Vec<AnomalousPeer>) -> Message {
+        Message::AnomalousPeers(anomalous_peers)
+    }
+}
+
+impl From<DataStatus> for Message {
+    fn from(data_status: DataStatus) -> Message {
+        Message::Data(Box::new(data_status))
+    }
+}
+
+impl From<Signature> for Message {
+    fn from(signature: Signature) -> Message {
+        Message::Signing(signature)
+    }
+}
+
+impl From<ConsensusState> for Message {
+    fn from(consensus_state: ConsensusState) -> Message {
+        Message::Consensus(Box::new(consensus_state))
+    }
+}
+
+impl From<MempoolStatus> for Message {
+    fn from(mempool_status: MempoolStatus) -> Message {
+        Message::Mempool(mempool_status)
+    }
+}
+
+impl From<CheckStatus> for Message {
+    fn from(check_status: CheckStatus) -> Message {
+        Message::Check(check_status)
+    }
+}
+
+impl From<CommandStatus> for Message {
+    fn from(command_status: CommandStatus) -> Message {
+        Message::Command(command_status)
+    }
+}
+
+impl From<LogEvent> for Message {
+    fn from(log_event: LogEvent) -> Message {
+        Message::LogEvent(log_event)
+    }
+}
+
+impl From<PrivValidatorState> for Message {
+    fn from(priv_validator_state: PrivValidatorState) -> Message {
+        Message::PrivValidator(priv_validator_state)
+    }
+}
+
+impl From<KeyAudit> for Message {
+    fn from(key_audit: KeyAudit) -> Message {
+        Message::Keys(key_audit)
+    }
+}
+
+impl From<NodeSettings> for Message {
+    fn from(node_settings: NodeSettings) -> Message {
+        Message::Settings(node_settings)
+    }
+}
+
+impl From<Vec<Metric>> for Message {
+    fn from(metrics: Vec<Metric>) -> Message {
+        Message::Metrics(metrics)
+    }
+}
+
+impl From<UpgradeReadiness> for Message {
+    fn from(readiness: UpgradeReadiness) -> Message {
+        Message::Upgrade(readiness)
+    }
+}
+
 /// Message envelope - contains information about the node events are
 /// originating from.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -1,140 +1,435 @@
 //! Tendermint node monitoring support
 
+pub mod check;
+pub mod command;
+pub mod consensus;
 pub mod data;
+pub mod keys;
+pub mod logs;
+pub mod mempool;
+pub mod metrics;
 pub mod net_info;
+pub mod outbox;
+pub mod priv_validator;
+pub mod reporter;
+pub mod rpc;
+pub mod scheduler;
+pub mod settings;
+pub mod signing;
 pub mod status;
+pub mod upgrade;
 
-use self::{data::Data, net_info::NetInfo, status::Status};
+pub use self::{check::Check, reporter::Reporter, scheduler::Scheduler};
+
+use self::{
+    command::ExternalCommand, consensus::Consensus, data::Data, keys::Keys, logs::Logs,
+    mempool::Mempool, metrics::Metrics, net_info::NetInfo, priv_validator::PrivValidator,
+    rpc::RpcClient, settings::Settings, signing::Signing, status::Status, upgrade::Upgrade,
+};
 use crate::{
     config,
-    error::{Error, ErrorKind},
+    error::Error,
     message::{self, Message},
+    net,
+    prelude::*,
+};
+use futures::{future, stream, StreamExt};
+use std::{path::Path, sync::Arc, time::Duration};
+use tendermint::block::Block;
+use tendermint_rpc::{
+    event::{Event, EventData},
+    query::{EventType, Query},
+    SubscriptionClient, WebSocketClient,
 };
-use std::{
-    thread,
-    time::{Duration, Instant},
+use tokio::{
+    sync::{mpsc, watch, Mutex},
+    time,
 };
 
-/// Default interval at which to poll a Tendermint node
-pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Default interval at which the node's status is polled
+pub const DEFAULT_STATUS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default interval at which the validator's signatures are checked
+pub const DEFAULT_SIGNING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default interval at which the node's consensus state is polled
+pub const DEFAULT_CONSENSUS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default interval at which the node's mempool is polled
+pub const DEFAULT_MEMPOOL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Default interval at which the node's peers are polled
+pub const DEFAULT_NET_INFO_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Default interval at which the node's database directory is sampled
+pub const DEFAULT_DATA_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Default interval at which the validator's signing state file is read
+pub const DEFAULT_PRIV_VALIDATOR_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default interval at which the node's key files are audited
+pub const DEFAULT_KEYS_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Default interval at which the node's settings are checked
+pub const DEFAULT_SETTINGS_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Default interval at which the node's Prometheus metrics are scraped
+pub const DEFAULT_METRICS_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Default interval at which the node's readiness for upgrades is checked
+pub const DEFAULT_UPGRADE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Default interval at which the node's log file is read
+pub const DEFAULT_LOGS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default interval at which external commands are run
+pub const DEFAULT_COMMAND_INTERVAL: Duration = Duration::from_secs(60);
 
 /// Default interval at which to provide a full node status report
 pub const DEFAULT_FULL_REPORT_INTERVAL: Duration = Duration::from_secs(60);
 
+/// Default amount of time to wait after an event subscription drops before
+/// attempting to resubscribe
+pub const DEFAULT_RESUBSCRIBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Registered check shared with the scheduler
+type SharedCheck = Arc<Mutex<Box<dyn Check>>>;
+
 /// Tendermint node monitor which performs various checks against the RPC
 /// interface or other signal sources.
 pub struct Monitor {
+    /// RPC address of the node
+    rpc_addr: net::Address,
+
     /// RPC client
-    rpc_client: tendermint_rpc::HttpClient,
+    rpc_client: RpcClient,
 
     /// Node status monitor
-    status: Status,
+    status: Arc<Mutex<Status>>,
 
     /// Network information monitor
-    net_info: NetInfo,
+    net_info: Arc<Mutex<NetInfo>>,
 
     /// Database directory monitor
-    data: Data,
+    data: Arc<Mutex<Data>>,
+
+    /// Validator signature monitor
+    signing: Arc<Mutex<Signing>>,
 
-    /// Interval at which we poll the node
-    poll_interval: Duration,
+    /// Consensus state monitor
+    consensus: Arc<Mutex<Consensus>>,
 
-    /// Interval after which a full report of the current state is made
-    full_report_interval: Duration,
+    /// Mempool monitor
+    mempool: Arc<Mutex<Mempool>>,
 
-    /// Last time a full report was made
-    last_full_report: Instant,
+    /// Validator signing state monitor
+    priv_validator: Arc<Mutex<PrivValidator>>,
 
-    /// Collector address
-    collector_addr: config::agent::CollectorAddr,
+    /// Key material audit
+    keys: Arc<Mutex<Keys>>,
+
+    /// Node settings monitor
+    settings: Arc<Mutex<Settings>>,
+
+    /// Prometheus metrics monitor (if the node has metrics enabled)
+    metrics: Option<Arc<Mutex<Metrics>>>,
+
+    /// Upgrade readiness monitor (if the node is run by cosmovisor)
+    upgrade: Option<Arc<Mutex<Upgrade>>>,
+
+    /// Log file monitor (if the node's log file is configured)
+    logs: Option<Arc<Mutex<Logs>>>,
+
+    /// External commands along with their scheduling config
+    commands: Vec<(config::agent::CommandConfig, Arc<Mutex<ExternalCommand>>)>,
+
+    /// Additional checks from the check registry
+    registered_checks: Vec<(&'static str, Duration, SharedCheck)>,
+
+    /// Scheduler which runs the checks
+    scheduler: Scheduler,
+
+    /// Amount of time to wait before attempting to resubscribe to events
+    resubscribe_interval: Duration,
+
+    /// Is the monitor currently subscribed to the node's events?
+    subscribed: watch::Sender<bool>,
 }
 
 impl Monitor {
-    /// Create a new `Monitor`
-    pub async fn new(agent_config: &config::agent::Config) -> Result<Self, Error> {
-        let home_dir = &agent_config.node_home;
-        let node_config = agent_config.load_tendermint_config()?;
-        let rpc_client = tendermint_rpc::HttpClient::new(node_config.rpc.laddr)?;
+    /// Create a new `Monitor` for the given node which sends messages to
+    /// the given reporter
+    pub async fn new(
+        agent_config: &config::agent::Config,
+        config: &config::agent::NodeConfig,
+        reporter: mpsc::UnboundedSender<message::Envelope>,
+    ) -> Result<Self, Error> {
+        let home_dir = &config.home;
+        let node_config = config.load_tendermint_config()?;
+        let rpc_addr = config.rpc_addr(&node_config)?;
+        let rpc_client = RpcClient::new(&rpc_addr)?;
         let status = Status::new(&rpc_client).await?;
+        let signing = Signing::new(status.validator.address);
         let data = Data::new(home_dir.join(&node_config.db_dir));
+        let priv_validator = PrivValidator::new(
+            home_dir.join(&node_config.priv_validator_state_file),
+            status.validator.address,
+        );
+        let mempool = Mempool::new(&node_config.mempool);
         let net_info = NetInfo::new(
             node_config.p2p.persistent_peers.clone(),
-            node_config.p2p.private_peer_ids,
+            node_config.p2p.private_peer_ids.clone(),
+        );
+
+        let keys = Keys::new(
+            home_dir.join(&node_config.node_key_file),
+            home_dir.join(
+                node_config
+                    .priv_validator_key_file
+                    .as_deref()
+                    .unwrap_or_else(|| Path::new("config/priv_validator_key.json")),
+            ),
+            node_config.priv_validator_laddr.is_some(),
+        );
+
+        let settings = Settings::new(config.config_toml_path(), config.app_toml_path());
+
+        let instrumentation = &node_config.instrumentation;
+        let metrics = if instrumentation.prometheus {
+            Some(Metrics::new(
+                &instrumentation.prometheus_listen_addr,
+                &instrumentation.namespace,
+                &agent_config.metrics_allowlist(),
+            )?)
+        } else {
+            None
+        };
+
+        let cosmovisor_dir = home_dir.join("cosmovisor");
+        let upgrade = if cosmovisor_dir.is_dir() {
+            Some(Upgrade::new(cosmovisor_dir))
+        } else {
+            None
+        };
+
+        let logs = config
+            .log_file
+            .as_ref()
+            .map(|log_file| Logs::new(log_file, &agent_config.log_patterns()))
+            .transpose()?;
+
+        let commands = agent_config
+            .commands
+            .iter()
+            .map(|command| {
+                let check = ExternalCommand::new(command, home_dir, &rpc_addr)?;
+                Ok((command.clone(), Arc::new(Mutex::new(check))))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        let registered_checks = check::instantiate(&check::Context {
+            node_config: config,
+            tendermint_config: &node_config,
+            network: &status.node.network,
+            node_id: status.node.id,
+        })?
+        .into_iter()
+        .map(|(name, interval, check)| (name, interval, Arc::new(Mutex::new(check))))
+        .collect();
+
+        let full_report_interval = agent_config
+            .full_report_interval_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_FULL_REPORT_INTERVAL);
+
+        let scheduler = Scheduler::new(
+            rpc_addr.clone(),
+            rpc_client.clone(),
+            status.node.network.clone(),
+            status.node.id,
+            agent_config.checks.clone(),
+            full_report_interval,
+            reporter,
         );
 
         Ok(Self {
+            rpc_addr,
             rpc_client,
-            status,
-            net_info,
-            data,
-            poll_interval: DEFAULT_POLL_INTERVAL,
-            full_report_interval: DEFAULT_FULL_REPORT_INTERVAL,
-            last_full_report: Instant::now() - DEFAULT_FULL_REPORT_INTERVAL,
-            collector_addr: agent_config.collector.clone(),
+            status: Arc::new(Mutex::new(status)),
+            net_info: Arc::new(Mutex::new(net_info)),
+            data: Arc::new(Mutex::new(data)),
+            signing: Arc::new(Mutex::new(signing)),
+            consensus: Arc::new(Mutex::new(Consensus::new())),
+            mempool: Arc::new(Mutex::new(mempool)),
+            priv_validator: Arc::new(Mutex::new(priv_validator)),
+            keys: Arc::new(Mutex::new(keys)),
+            settings: Arc::new(Mutex::new(settings)),
+            metrics: metrics.map(|metrics| Arc::new(Mutex::new(metrics))),
+            upgrade: upgrade.map(|upgrade| Arc::new(Mutex::new(upgrade))),
+            logs: logs.map(|logs| Arc::new(Mutex::new(logs))),
+            commands,
+            registered_checks,
+            scheduler,
+            resubscribe_interval: DEFAULT_RESUBSCRIBE_INTERVAL,
+            subscribed: watch::channel(false).0,
         })
     }
 
-    /// Run the monitor
-    pub async fn run(&mut self) {
+    /// Run the monitor.
+    ///
+    /// Each check runs concurrently at its own interval. The monitor also
+    /// subscribes to the node's `NewBlock` and `NewRound` events so changes
+    /// are reported as soon as they happen, resubscribing whenever the
+    /// subscription drops. While subscribed, the checks covered by the
+    /// events only poll the node when a full report is due.
+    ///
+    /// Nodes whose RPC is only reachable over a Unix domain socket don't
+    /// support event subscriptions and are only polled.
+    pub async fn run(self) {
+        let scheduler = &self.scheduler;
+
+        let mut tasks = vec![
+            scheduler.spawn_event_driven(
+                "status",
+                DEFAULT_STATUS_INTERVAL,
+                self.status.clone(),
+                self.subscribed.subscribe(),
+            ),
+            scheduler.spawn_event_driven(
+                "signing",
+                DEFAULT_SIGNING_INTERVAL,
+                self.signing.clone(),
+                self.subscribed.subscribe(),
+            ),
+            scheduler.spawn_event_driven(
+                "consensus",
+                DEFAULT_CONSENSUS_INTERVAL,
+                self.consensus.clone(),
+                self.subscribed.subscribe(),
+            ),
+            scheduler.spawn("mempool", DEFAULT_MEMPOOL_INTERVAL, self.mempool.clone()),
+            scheduler.spawn("net_info", DEFAULT_NET_INFO_INTERVAL, self.net_info.clone()),
+            scheduler.spawn("data", DEFAULT_DATA_INTERVAL, self.data.clone()),
+            scheduler.spawn(
+                "priv_validator",
+                DEFAULT_PRIV_VALIDATOR_INTERVAL,
+                self.priv_validator.clone(),
+            ),
+            scheduler.spawn("keys", DEFAULT_KEYS_INTERVAL, self.keys.clone()),
+            scheduler.spawn("settings", DEFAULT_SETTINGS_INTERVAL, self.settings.clone()),
+        ];
+
+        if let Some(metrics) = &self.metrics {
+            tasks.push(scheduler.spawn("metrics", DEFAULT_METRICS_INTERVAL, metrics.clone()));
+        }
+
+        if let Some(upgrade) = &self.upgrade {
+            tasks.push(scheduler.spawn("upgrade", DEFAULT_UPGRADE_INTERVAL, upgrade.clone()));
+        }
+
+        if let Some(logs) = &self.logs {
+            tasks.push(scheduler.spawn("logs", DEFAULT_LOGS_INTERVAL, logs.clone()));
+        }
+
+        // Commands are namespaced so they can't be confused with checks
+        for (command, check) in &self.commands {
+            tasks.push(scheduler.spawn_with_config(
+                &format!("command:{}", command.name),
+                &command.schedule(),
+                DEFAULT_COMMAND_INTERVAL,
+                check.clone(),
+            ));
+        }
+
+        for (name, interval, check) in &self.registered_checks {
+            tasks.push(scheduler.spawn(name, *interval, check.clone()));
+        }
+
+        let websocket_url = match self.rpc_client.websocket_url() {
+            Some(url) => url,
+            None => {
+                info!("[{}] polling node (no event subscriptions)", &self.rpc_addr);
+                future::join_all(tasks).await;
+                return;
+            }
+        };
+
         loop {
-            match self.poll().await {
-                Ok(msg) => {
-                    if let Some(env) = message::Envelope::new(
-                        self.status.node.network.clone(),
-                        self.status.node.id,
-                        msg,
-                    ) {
-                        self.report(env).await.unwrap();
-                    }
-                }
-                Err(e) => {
-                    status_err!("error polling node: {}", e);
-                    break;
-                }
+            let result = self.subscribe(&websocket_url).await;
+
+            // Fall back to polling until we've resubscribed
+            self.subscribed.send_replace(false);
+
+            match result {
+                Ok(()) => warn!("[{}] event subscription closed", &self.rpc_addr),
+                Err(e) => warn!("[{}] event subscription failed: {}", &self.rpc_addr, e),
             }
 
-            thread::sleep(self.poll_interval);
+            time::sleep(self.resubscribe_interval).await;
         }
     }
 
-    /// Poll the node, collecting messages about events we're interested in
-    async fn poll(&mut self) -> Result<Vec<Message>, Error> {
-        let force = self.should_force();
+    /// Subscribe to node events, processing them until the subscription drops
+    async fn subscribe(&self, websocket_url: &str) -> Result<(), Error> {
+        let (client, driver) = WebSocketClient::new(websocket_url).await?;
+        let driver_handle = tokio::spawn(async move { driver.run().await });
+        let result = self.process_events(&client).await;
+
+        client.close()?;
 
-        let mut messages = vec![];
-        messages.extend(self.status.update(&self.rpc_client, force).await?);
-        messages.extend(self.net_info.update(&self.rpc_client, force).await?);
-        messages.extend(self.data.update(force)?);
-        Ok(messages)
+        if let Ok(Err(e)) = driver_handle.await {
+            warn!("websocket client error: {}", e);
+        }
+
+        result
     }
 
-    /// Determine if we need to force updates in order to generate a full report
-    fn should_force(&mut self) -> bool {
-        if self.last_full_report.elapsed() >= self.full_report_interval {
-            self.last_full_report = Instant::now();
-            true
-        } else {
-            false
+    /// Process events from the given subscription client
+    async fn process_events(&self, client: &WebSocketClient) -> Result<(), Error> {
+        let new_blocks = client.subscribe(EventType::NewBlock.into()).await?;
+        let new_rounds = client.subscribe(Query::eq("tm.event", "NewRound")).await?;
+        let mut events = stream::select(new_blocks, new_rounds);
+
+        info!("subscribed to events from {}", &self.rpc_addr);
+        self.subscribed.send_replace(true);
+
+        // Only errors from the event stream itself end the subscription
+        while let Some(event) = events.next().await {
+            match self.handle_event(event?).await {
+                Ok(msg) => self.scheduler.send(msg),
+                Err(e) => warn!("[{}] error handling event: {}", &self.rpc_addr, e),
+            }
         }
+
+        Ok(())
     }
 
-    async fn report(&self, msg: message::Envelope) -> Result<(), Error> {
-        let url = match &self.collector_addr {
-            config::agent::CollectorAddr::Http(config::agent::HttpConfig { uri }) => {
-                format!("{}/collector", uri)
+    /// Handle an incoming event, collecting messages about changes
+    async fn handle_event(&self, event: Event) -> Result<Vec<Message>, Error> {
+        match event.data {
+            EventData::NewBlock {
+                block: Some(block), ..
+            } => Ok(self.handle_new_block(&block).await),
+            _ => {
+                debug!("got event: {}", event.query);
+                self.consensus
+                    .lock()
+                    .await
+                    .update(&self.rpc_client, false)
+                    .await
             }
-        };
+        }
+    }
 
-        let client = reqwest::Client::new();
-        let res = client
-            .post(&url)
-            .body(msg.to_json())
-            .send()
-            .await
-            .map_err(|e| format_err!(ErrorKind::ReportError, "{}", e))?;
+    /// Handle a newly committed block
+    async fn handle_new_block(&self, block: &Block) -> Vec<Message> {
+        let mut status = self.status.lock().await;
+        let mut messages = status.apply_block(block);
 
-        res.error_for_status()
-            .map_err(|e| format_err!(ErrorKind::ReportError, "{}", e))?;
-        Ok(())
+        if status.is_validator() {
+            messages.extend(self.signing.lock().await.apply_block(block));
+        }
+
+        messages
     }
 }
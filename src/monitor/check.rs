@@ -0,0 +1,247 @@
+//! Checks performed by the monitor.
+//!
+//! Besides the built-in checks, additional checks can be added to the agent
+//! by registering them with [`register`] before the application is booted,
+//! which allows downstream crates to provide chain-specific checks without
+//! modifying cosmon itself.
+
+use super::{
+    consensus::Consensus, data::Data, mempool::Mempool, message::Message, net_info::NetInfo,
+    rpc::RpcClient, signing::Signing, status::Status,
+};
+use crate::{
+    config::agent::{NodeConfig, TendermintConfig},
+    prelude::*,
+};
+use async_trait::async_trait;
+use std::{sync::Mutex, time::Duration};
+use tendermint::{chain, node};
+
+/// Names of the built-in checks (which can't be used by registered checks)
+pub const BUILTIN_CHECKS: &[&str] = &[
+    "status",
+    "signing",
+    "consensus",
+    "mempool",
+    "net_info",
+    "data",
+    "logs",
+    "priv_validator",
+    "keys",
+    "settings",
+    "metrics",
+    "upgrade",
+];
+
+/// Global registry of additional checks
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry::new());
+
+/// Checks are sources of messages which are run periodically by the
+/// monitor's scheduler.
+#[async_trait]
+pub trait Check: Send {
+    /// Run the check using the given RPC client, returning messages about
+    /// any changes (or the full current state if `force` is set)
+    async fn update(&mut self, rpc_client: &RpcClient, force: bool) -> Result<Vec<Message>, Error>;
+}
+
+#[async_trait]
+impl<C: Check + ?Sized> Check for Box<C> {
+    async fn update(&mut self, rpc_client: &RpcClient, force: bool) -> Result<Vec<Message>, Error> {
+        (**self).update(rpc_client, force).await
+    }
+}
+
+/// Information about the node a registered check is being created for
+#[derive(Debug)]
+pub struct Context<'a> {
+    /// Agent configuration of the node
+    pub node_config: &'a NodeConfig,
+
+    /// The node's `config.toml`
+    pub tendermint_config: &'a TendermintConfig,
+
+    /// Chain ID of the node
+    pub network: &'a chain::Id,
+
+    /// ID of the node
+    pub node_id: node::Id,
+}
+
+/// Function which creates an instance of a registered check for a node
+pub type Factory = dyn Fn(&Context<'_>) -> Result<Box<dyn Check>, Error> + Send + Sync;
+
+/// Instance of a registered check along with its name and default interval
+pub type Instance = (&'static str, Duration, Box<dyn Check>);
+
+/// Registry of additional checks which are run for every monitored node
+#[derive(Default)]
+pub struct Registry {
+    /// Registered checks
+    entries: Vec<Entry>,
+}
+
+/// Check which has been registered with the registry
+struct Entry {
+    /// Name of the check
+    name: &'static str,
+
+    /// Interval at which the check runs unless configured otherwise
+    default_interval: Duration,
+
+    /// Function which creates instances of the check
+    factory: Box<Factory>,
+}
+
+impl Registry {
+    /// Create a new, empty registry
+    pub const fn new() -> Self {
+        Self { entries: vec![] }
+    }
+
+    /// Register a check with the given name and default interval.
+    ///
+    /// The name is used to configure the check under `[agent.checks.<name>]`
+    /// and must be unique.
+    pub fn register<F>(
+        &mut self,
+        name: &'static str,
+        default_interval: Duration,
+        factory: F,
+    ) -> Result<(), Error>
+    where
+        F: Fn(&Context<'_>) -> Result<Box<dyn Check>, Error> + Send + Sync + 'static,
+    {
+        if BUILTIN_CHECKS.contains(&name) || self.entries.iter().any(|entry| entry.name == name) {
+            fail!(ErrorKind::ConfigError, "check already registered: {}", name);
+        }
+
+        self.entries.push(Entry {
+            name,
+            default_interval,
+            factory: Box::new(factory),
+        });
+
+        Ok(())
+    }
+
+    /// Create instances of all registered checks for the given node,
+    /// returning them along with their names and default intervals
+    pub fn instantiate(&self, context: &Context<'_>) -> Result<Vec<Instance>, Error> {
+        self.entries
+            .iter()
+            .map(|entry| {
+                Ok((
+                    entry.name,
+                    entry.default_interval,
+                    (entry.factory)(context)?,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Register a check with the global registry.
+///
+/// Must be called before the agent is started (i.e. before booting the
+/// application) for the check to be run.
+pub fn register<F>(name: &'static str, default_interval: Duration, factory: F) -> Result<(), Error>
+where
+    F: Fn(&Context<'_>) -> Result<Box<dyn Check>, Error> + Send + Sync + 'static,
+{
+    REGISTRY
+        .lock()
+        .unwrap()
+        .register(name, default_interval, factory)
+}
+
+/// Create instances of all checks in the global registry for the given node
+pub fn instantiate(context: &Context<'_>) -> Result<Vec<Instance>, Error> {
+    REGISTRY.lock().unwrap().instantiate(context)
+}
+
+#[async_trait]
+impl Check for Status {
+    async fn update(&mut self, rpc_client: &RpcClient, force: bool) -> Result<Vec<Message>, Error> {
+        Status::update(self, rpc_client, force).await
+    }
+}
+
+#[async_trait]
+impl Check for Signing {
+    async fn update(
+        &mut self,
+        rpc_client: &RpcClient,
+        _force: bool,
+    ) -> Result<Vec<Message>, Error> {
+        Signing::update(self, rpc_client).await
+    }
+}
+
+#[async_trait]
+impl Check for Consensus {
+    async fn update(&mut self, rpc_client: &RpcClient, force: bool) -> Result<Vec<Message>, Error> {
+        Consensus::update(self, rpc_client, force).await
+    }
+}
+
+#[async_trait]
+impl Check for Mempool {
+    async fn update(
+        &mut self,
+        rpc_client: &RpcClient,
+        _force: bool,
+    ) -> Result<Vec<Message>, Error> {
+        Mempool::update(self, rpc_client).await
+    }
+}
+
+#[async_trait]
+impl Check for NetInfo {
+    async fn update(&mut self, rpc_client: &RpcClient, force: bool) -> Result<Vec<Message>, Error> {
+        NetInfo::update(self, rpc_client, force).await
+    }
+}
+
+#[async_trait]
+impl Check for Data {
+    async fn update(
+        &mut self,
+        _rpc_client: &RpcClient,
+        _force: bool,
+    ) -> Result<Vec<Message>, Error> {
+        Data::update(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NoopCheck;
+
+    #[async_trait]
+    impl Check for NoopCheck {
+        async fn update(
+            &mut self,
+            _rpc_client: &RpcClient,
+            _force: bool,
+        ) -> Result<Vec<Message>, Error> {
+            Ok(vec![])
+        }
+    }
+
+    fn noop(_context: &Context<'_>) -> Result<Box<dyn Check>, Error> {
+        Ok(Box::new(NoopCheck))
+    }
+
+    #[test]
+    fn rejects_duplicate_names() {
+        let mut registry = Registry::new();
+        let interval = Duration::from_secs(1);
+
+        registry.register("noop", interval, noop).unwrap();
+        assert!(registry.register("noop", interval, noop).is_err());
+        assert!(registry.register("status", interval, noop).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+//! External command checks (Nagios plugin style)
+
+use super::{check::Check, message::Message, rpc::RpcClient};
+use crate::{config::agent::CommandConfig, net, prelude::*};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+use tokio::{process, time};
+
+/// Maximum length of command output which is reported to the collector
+pub const MAX_OUTPUT_LEN: usize = 1024;
+
+/// External command check: runs a command and interprets its exit code
+/// using the Nagios plugin conventions.
+///
+/// The command is run with `COSMON_NODE_HOME` and `COSMON_RPC_ADDR` set in
+/// its environment so the same script can be used for several nodes.
+/// Commands which time out are killed and reported as critical, like Nagios
+/// does by default.
+#[derive(Clone, Debug)]
+pub struct ExternalCommand {
+    /// Name of the command
+    name: String,
+
+    /// Program to run followed by its arguments
+    argv: Vec<String>,
+
+    /// Amount of time the command may take before it's killed
+    timeout: Duration,
+
+    /// Home directory of the node
+    node_home: PathBuf,
+
+    /// RPC address of the node
+    rpc_addr: net::Address,
+
+    /// Last reported status
+    last_status: Option<CommandStatus>,
+}
+
+impl ExternalCommand {
+    /// Create a new external command check for the given node
+    pub fn new(
+        config: &CommandConfig,
+        node_home: &Path,
+        rpc_addr: &net::Address,
+    ) -> Result<Self, Error> {
+        if config.command.is_empty() {
+            fail!(
+                ErrorKind::ConfigError,
+                "no command given for command check: {}",
+                config.name
+            );
+        }
+
+        Ok(Self {
+            name: config.name.clone(),
+            argv: config.command.clone(),
+            timeout: config.timeout(),
+            node_home: node_home.to_owned(),
+            rpc_addr: rpc_addr.clone(),
+            last_status: None,
+        })
+    }
+
+    /// Run the command, returning its status
+    async fn run(&self) -> Result<CommandStatus, Error> {
+        let command = process::Command::new(&self.argv[0])
+            .args(&self.argv[1..])
+            .env("COSMON_NODE_HOME", &self.node_home)
+            .env("COSMON_RPC_ADDR", self.rpc_addr.to_string())
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output();
+
+        let output = match time::timeout(self.timeout, command).await {
+            Ok(result) => result.map_err(|e| {
+                format_err!(ErrorKind::IoError, "couldn't run {}: {}", &self.argv[0], e)
+            })?,
+            Err(_) => {
+                return Ok(CommandStatus {
+                    name: self.name.clone(),
+                    state: CommandState::Critical,
+                    exit_code: None,
+                    output: format!("timed out after {}s", self.timeout.as_secs()),
+                })
+            }
+        };
+
+        // Only the first line of output is reported, like Nagios does
+        let mut text = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_owned();
+
+        if text.len() > MAX_OUTPUT_LEN {
+            let mut end = MAX_OUTPUT_LEN;
+
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+
+            text.truncate(end);
+        }
+
+        Ok(CommandStatus {
+            name: self.name.clone(),
+            state: output.status.code().into(),
+            exit_code: output.status.code(),
+            output: text,
+        })
+    }
+}
+
+#[async_trait]
+impl Check for ExternalCommand {
+    async fn update(
+        &mut self,
+        _rpc_client: &RpcClient,
+        force: bool,
+    ) -> Result<Vec<Message>, Error> {
+        let status = self.run().await?;
+        let mut output = vec![];
+
+        let changed = match &self.last_status {
+            Some(last_status) => last_status.state != status.state,
+            None => true,
+        };
+
+        if changed || force {
+            output.push(status.clone().into());
+        }
+
+        self.last_status = Some(status);
+        Ok(output)
+    }
+}
+
+/// Result of running an external command
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CommandStatus {
+    /// Name of the command
+    pub name: String,
+
+    /// State indicated by the command's exit code
+    pub state: CommandState,
+
+    /// Exit code of the command (if it wasn't terminated by a signal)
+    pub exit_code: Option<i32>,
+
+    /// First line of the command's output
+    pub output: String,
+}
+
+/// State reported by an external command
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum CommandState {
+    /// Exit code 0
+    #[serde(rename = "ok")]
+    Ok,
+
+    /// Exit code 1
+    #[serde(rename = "warning")]
+    Warning,
+
+    /// Exit code 2
+    #[serde(rename = "critical")]
+    Critical,
+
+    /// Exit code 3 (or any other exit code)
+    #[serde(rename = "unknown")]
+    Unknown,
+}
+
+impl From<Option<i32>> for CommandState {
+    fn from(exit_code: Option<i32>) -> CommandState {
+        match exit_code {
+            Some(0) => CommandState::Ok,
+            Some(1) => CommandState::Warning,
+            Some(2) => CommandState::Critical,
+            _ => CommandState::Unknown,
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Consensus state monitor
+
+use super::{message::Message, rpc::RpcClient};
+use crate::prelude::*;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tendermint::block;
+use tendermint_rpc::{endpoint::consensus_state::RoundVotes, Client};
+
+/// Consensus state monitor: monitors the `/consensus_state` and
+/// `/dump_consensus_state` RPC endpoints.
+///
+/// Tracks the node's current height/round/step and how long it has been
+/// working on the current height, along with the heights and rounds of its
+/// peers (which tell a stuck node apart from a halted chain).
+#[derive(Clone, Debug, Default)]
+pub struct Consensus {
+    /// Last consensus state
+    state: Option<ConsensusState>,
+}
+
+impl Consensus {
+    /// Create a new consensus state monitor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update internal state using the given RPC client, returning any changes
+    pub async fn update(
+        &mut self,
+        rpc_client: &RpcClient,
+        force: bool,
+    ) -> Result<Vec<Message>, Error> {
+        let round_state = rpc_client.consensus_state().await?.round_state;
+        let dump = rpc_client
+            .get::<DumpConsensusState>("/dump_consensus_state")
+            .await?;
+        let hrs = &round_state.height_round_step;
+        let height = hrs.height;
+
+        let height_since = match &self.state {
+            Some(state) if state.height == height => state.height_since,
+            _ => Utc::now(),
+        };
+
+        let state = ConsensusState {
+            height,
+            round: u32::from(hrs.round),
+            step: i64::from(hrs.step),
+            height_since,
+            votes: round_state
+                .height_vote_set
+                .iter()
+                .map(RoundVoteBits::from)
+                .collect(),
+            peers: dump
+                .peers
+                .into_iter()
+                .map(|peer| PeerRoundState {
+                    node_address: peer.node_address,
+                    height: peer.peer_state.round_state.height,
+                    round: peer.peer_state.round_state.round,
+                })
+                .collect(),
+        };
+
+        let mut output = vec![];
+
+        let changed = match &self.state {
+            Some(prev) => {
+                prev.height != state.height || prev.round != state.round || prev.step != state.step
+            }
+            None => true,
+        };
+
+        if changed || force {
+            output.push(state.clone().into());
+        }
+
+        self.state = Some(state);
+        Ok(output)
+    }
+}
+
+/// Consensus state of a node
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ConsensusState {
+    /// Height the node is currently trying to commit
+    pub height: block::Height,
+
+    /// Current round
+    pub round: u32,
+
+    /// Current step within the round (see `RoundStepType` in Tendermint)
+    pub step: i64,
+
+    /// Time at which the node was first observed at the current height
+    pub height_since: DateTime<Utc>,
+
+    /// Prevotes and precommits received for each round at this height
+    pub votes: Vec<RoundVoteBits>,
+
+    /// Heights and rounds of the node's peers
+    #[serde(default)]
+    pub peers: Vec<PeerRoundState>,
+}
+
+impl ConsensusState {
+    /// Get the votes for the current round (if any have been received)
+    pub fn current_round_votes(&self) -> Option<&RoundVoteBits> {
+        self.votes.iter().find(|votes| votes.round == self.round)
+    }
+
+    /// Number of peers which are at a higher height than the node
+    pub fn peers_ahead(&self) -> usize {
+        self.peers
+            .iter()
+            .filter(|peer| peer.height > self.height)
+            .count()
+    }
+}
+
+/// Height and round of one of the node's peers
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PeerRoundState {
+    /// Address of the peer (i.e. `id@host:port`)
+    pub node_address: String,
+
+    /// Height the peer is working on
+    pub height: block::Height,
+
+    /// Round the peer is in (-1 until it has been heard from)
+    pub round: i32,
+}
+
+/// Response from the `/dump_consensus_state` endpoint (only the parts
+/// describing the node's peers)
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct DumpConsensusState {
+    /// Consensus state of each peer
+    peers: Vec<DumpPeer>,
+}
+
+impl tendermint_rpc::Response for DumpConsensusState {}
+
+/// Peer in a `/dump_consensus_state` response
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct DumpPeer {
+    /// Address of the peer
+    node_address: String,
+
+    /// State of the peer as tracked by the node
+    peer_state: DumpPeerState,
+}
+
+/// State of a peer in a `/dump_consensus_state` response
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct DumpPeerState {
+    /// Round state of the peer
+    round_state: DumpPeerRoundState,
+}
+
+/// Round state of a peer in a `/dump_consensus_state` response
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct DumpPeerRoundState {
+    /// Height the peer is working on
+    height: block::Height,
+
+    /// Round the peer is in
+    round: i32,
+}
+
+/// Prevote and precommit bit arrays for a particular round
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct RoundVoteBits {
+    /// Round number
+    pub round: u32,
+
+    /// Prevotes received
+    pub prevotes: VoteBits,
+
+    /// Precommits received
+    pub precommits: VoteBits,
+}
+
+impl<'a> From<&'a RoundVotes> for RoundVoteBits {
+    fn from(votes: &'a RoundVotes) -> RoundVoteBits {
+        RoundVoteBits {
+            round: votes.round,
+            prevotes: VoteBits::parse(&votes.prevotes_bit_array),
+            precommits: VoteBits::parse(&votes.precommits_bit_array),
+        }
+    }
+}
+
+/// Votes received from validators, along with their voting power
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct VoteBits {
+    /// Bit array of validators whose votes were received (e.g. `BA{4:xx_x}`)
+    pub bit_array: String,
+
+    /// Voting power of the received votes
+    pub voted_power: u64,
+
+    /// Total voting power of the validator set
+    pub total_power: u64,
+}
+
+impl VoteBits {
+    /// Parse a vote bit array as rendered by Tendermint's RPC, e.g.
+    /// `BA{4:xx_x} 3000/4000 = 0.75`
+    pub fn parse(s: &str) -> Self {
+        let mut parts = s.splitn(2, ' ');
+        let bit_array = parts.next().unwrap_or_default().to_owned();
+
+        let (voted_power, total_power) = parts
+            .next()
+            .and_then(|rest| rest.split(" = ").next())
+            .and_then(|fraction| {
+                let mut fraction = fraction.splitn(2, '/');
+                let voted = fraction.next()?.trim().parse().ok()?;
+                let total = fraction.next()?.trim().parse().ok()?;
+                Some((voted, total))
+            })
+            .unwrap_or((0, 0));
+
+        Self {
+            bit_array,
+            voted_power,
+            total_power,
+        }
+    }
+
+    /// Percentage of the total voting power which has not voted
+    pub fn missing_percent(&self) -> u64 {
+        if self.total_power == 0 {
+            return 0;
+        }
+
+        self.total_power.saturating_sub(self.voted_power) * 100 / self.total_power
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VoteBits;
+
+    #[test]
+    fn parse_vote_bits() {
+        let bits = VoteBits::parse("BA{4:xx_x} 3000/4000 = 0.75");
+        assert_eq!(bits.bit_array, "BA{4:xx_x}");
+        assert_eq!(bits.voted_power, 3000);
+        assert_eq!(bits.total_power, 4000);
+        assert_eq!(bits.missing_percent(), 25);
+    }
+
+    #[test]
+    fn parse_malformed_vote_bits() {
+        let bits = VoteBits::parse("nil-VoteSet");
+        assert_eq!(bits.bit_array, "nil-VoteSet");
+        assert_eq!(bits.missing_percent(), 0);
+    }
+}
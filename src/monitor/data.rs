@@ -1,27 +1,204 @@
 //! Database directory monitor
 
 use super::message::Message;
-use crate::error::Error;
-use std::path::PathBuf;
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// Databases inside of the `data/` directory which are reported individually
+pub const DATABASES: &[&str] = &[
+    "blockstore.db",
+    "state.db",
+    "tx_index.db",
+    "application.db",
+    "cs.wal",
+];
+
+/// Window over which the growth rate of the database directory is computed
+pub const GROWTH_RATE_WINDOW: Duration = Duration::from_secs(60 * 60);
 
 /// Database directory monitor: monitors the `data/` directory
-// TODO(tarcieri): actually implement this properly
 #[derive(Clone, Debug)]
 pub struct Data {
     /// Path to the database
-    #[allow(dead_code)]
     path: PathBuf,
+
+    /// Previous samples of the total database size, oldest first
+    samples: VecDeque<(Instant, u64)>,
 }
 
 impl Data {
-    /// Create a new network information monitor
+    /// Create a new database directory monitor
     pub fn new(path: impl Into<PathBuf>) -> Self {
-        Self { path: path.into() }
+        Self {
+            path: path.into(),
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Sample the database directory, returning its current status
+    pub async fn update(&mut self) -> Result<Vec<Message>, Error> {
+        Ok(vec![self.sample().await?.into()])
+    }
+
+    /// Measure the database directory and the filesystem it resides on.
+    ///
+    /// Walking the directory can take a while for large databases, so it's
+    /// done on a blocking thread.
+    async fn sample(&mut self) -> Result<DataStatus, Error> {
+        let path = self.path.clone();
+        let usage = tokio::task::spawn_blocking(move || Usage::measure(&path))
+            .await
+            .map_err(|e| format_err!(ErrorKind::IoError, "data directory walk failed: {}", e))??;
+
+        let total_bytes = usage.total_bytes;
+        let now = Instant::now();
+
+        while let Some((sampled_at, _)) = self.samples.front() {
+            if now.duration_since(*sampled_at) > GROWTH_RATE_WINDOW {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        let growth_rate = match self.samples.front() {
+            Some((sampled_at, size)) => {
+                let elapsed = now.duration_since(*sampled_at).as_secs();
+
+                if elapsed > 0 {
+                    (total_bytes as i64 - *size as i64) / elapsed as i64
+                } else {
+                    0
+                }
+            }
+            None => 0,
+        };
+
+        self.samples.push_back((now, total_bytes));
+
+        Ok(DataStatus {
+            path: self.path.clone(),
+            databases: usage.databases,
+            total_bytes,
+            growth_rate,
+            available_bytes: usage.available_bytes,
+            filesystem_bytes: usage.filesystem_bytes,
+        })
+    }
+}
+
+/// Disk usage of the database directory
+struct Usage {
+    /// Sizes of the individual databases in bytes
+    databases: Map<String, u64>,
+
+    /// Total size of the database directory in bytes
+    total_bytes: u64,
+
+    /// Space available on the underlying filesystem in bytes
+    available_bytes: u64,
+
+    /// Total size of the underlying filesystem in bytes
+    filesystem_bytes: u64,
+}
+
+impl Usage {
+    /// Measure the database directory in a single walk, summing the sizes of
+    /// the individual databases into the total
+    fn measure(path: &Path) -> Result<Self, io::Error> {
+        let mut databases = Map::new();
+        let mut total_bytes = 0;
+
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+
+            let size = match dir_size(&entry.path()) {
+                Ok(size) => size,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+
+            let name = entry.file_name();
+
+            if let Some(name) = DATABASES.iter().find(|db| name == **db) {
+                databases.insert(name.to_string(), size);
+            }
+
+            total_bytes += size;
+        }
+
+        Ok(Self {
+            databases,
+            total_bytes,
+            available_bytes: fs2::available_space(path)?,
+            filesystem_bytes: fs2::total_space(path)?,
+        })
+    }
+}
+
+/// Database directory status
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DataStatus {
+    /// Path to the database directory
+    pub path: PathBuf,
+
+    /// Sizes of the individual databases in bytes
+    pub databases: Map<String, u64>,
+
+    /// Total size of the database directory in bytes
+    pub total_bytes: u64,
+
+    /// Growth rate of the database directory in bytes per second
+    pub growth_rate: i64,
+
+    /// Space available on the underlying filesystem in bytes
+    pub available_bytes: u64,
+
+    /// Total size of the underlying filesystem in bytes
+    pub filesystem_bytes: u64,
+}
+
+impl DataStatus {
+    /// Estimate how long until the filesystem fills up at the current
+    /// growth rate (if it's growing at all)
+    pub fn time_until_full(&self) -> Option<Duration> {
+        if self.growth_rate > 0 {
+            Some(Duration::from_secs(
+                self.available_bytes / self.growth_rate as u64,
+            ))
+        } else {
+            None
+        }
     }
+}
+
+/// Compute the total size of all files under the given path
+fn dir_size(path: &Path) -> Result<u64, io::Error> {
+    let metadata = fs::symlink_metadata(path)?;
 
-    /// Update internal state, returning any changes
-    pub fn update(&mut self, _force: bool) -> Result<Vec<Message>, Error> {
-        let output = vec![];
-        Ok(output)
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
     }
+
+    let mut size = 0;
+
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+
+        match dir_size(&entry.path()) {
+            Ok(entry_size) => size += entry_size,
+            // Files may be removed while we're walking the directory
+            // (e.g. during compactions)
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(size)
 }
@@ -0,0 +1,176 @@
+//! Key material audit: presence and permissions of the node's key files
+
+use super::{check::Check, message::Message, rpc::RpcClient};
+use crate::prelude::*;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+};
+use tendermint::account;
+use tendermint_rpc::Client;
+
+/// Key material audit: reports on `node_key.json` and
+/// `priv_validator_key.json`, and whether a remote signer is in use.
+#[derive(Clone, Debug)]
+pub struct Keys {
+    /// Path to `node_key.json`
+    node_key_path: PathBuf,
+
+    /// Path to `priv_validator_key.json`
+    priv_validator_key_path: PathBuf,
+
+    /// Is `priv_validator_laddr` set?
+    remote_signer: bool,
+
+    /// Last reported audit
+    last_audit: Option<KeyAudit>,
+}
+
+impl Keys {
+    /// Create a new key material audit
+    pub fn new(
+        node_key_path: impl Into<PathBuf>,
+        priv_validator_key_path: impl Into<PathBuf>,
+        remote_signer: bool,
+    ) -> Self {
+        Self {
+            node_key_path: node_key_path.into(),
+            priv_validator_key_path: priv_validator_key_path.into(),
+            remote_signer,
+            last_audit: None,
+        }
+    }
+}
+
+#[async_trait]
+impl Check for Keys {
+    async fn update(&mut self, rpc_client: &RpcClient, force: bool) -> Result<Vec<Message>, Error> {
+        let validator = rpc_client.status().await?.validator_info;
+        let priv_validator_key = KeyFile::stat(&self.priv_validator_key_path)?;
+
+        // The consensus key on disk is the one the node is validating with
+        let consensus_key_on_disk = validator.power.value() > 0
+            && priv_validator_key.exists
+            && key_address(&self.priv_validator_key_path)? == Some(validator.address);
+
+        let audit = KeyAudit {
+            remote_signer: self.remote_signer,
+            consensus_key_on_disk,
+            node_key: KeyFile::stat(&self.node_key_path)?,
+            priv_validator_key,
+        };
+
+        let mut output = vec![];
+
+        if self.last_audit.as_ref() != Some(&audit) || force {
+            output.push(audit.clone().into());
+            self.last_audit = Some(audit);
+        }
+
+        Ok(output)
+    }
+}
+
+/// Results of a key material audit
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct KeyAudit {
+    /// Is a remote signer in use (i.e. is `priv_validator_laddr` set)?
+    pub remote_signer: bool,
+
+    /// Is the active validator's consensus key stored in plaintext in
+    /// `priv_validator_key.json`?
+    pub consensus_key_on_disk: bool,
+
+    /// `node_key.json`
+    pub node_key: KeyFile,
+
+    /// `priv_validator_key.json`
+    pub priv_validator_key: KeyFile,
+}
+
+impl KeyAudit {
+    /// Paths of any key files which are readable by other users
+    pub fn world_readable(&self) -> Vec<&Path> {
+        vec![&self.node_key, &self.priv_validator_key]
+            .into_iter()
+            .filter(|key_file| key_file.is_world_readable())
+            .map(|key_file| key_file.path.as_path())
+            .collect()
+    }
+}
+
+/// Ownership and permissions of a key file
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct KeyFile {
+    /// Path to the key file
+    pub path: PathBuf,
+
+    /// Does the key file exist?
+    pub exists: bool,
+
+    /// User ID of the owner
+    pub uid: Option<u32>,
+
+    /// Group ID of the owner
+    pub gid: Option<u32>,
+
+    /// Permission bits
+    pub mode: Option<u32>,
+}
+
+impl KeyFile {
+    /// Look up the ownership and permissions of the given key file
+    pub fn stat(path: &Path) -> Result<Self, io::Error> {
+        match fs::metadata(path) {
+            Ok(metadata) => Ok(Self {
+                path: path.to_owned(),
+                exists: true,
+                uid: Some(metadata.uid()),
+                gid: Some(metadata.gid()),
+                mode: Some(metadata.mode() & 0o7777),
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self {
+                path: path.to_owned(),
+                exists: false,
+                uid: None,
+                gid: None,
+                mode: None,
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Is the key file readable by other users?
+    pub fn is_world_readable(&self) -> bool {
+        self.mode.map(|mode| mode & 0o004 != 0).unwrap_or(false)
+    }
+}
+
+/// Read the address of the key in `priv_validator_key.json`
+fn key_address(path: &Path) -> Result<Option<account::Id>, Error> {
+    /// Fields of `priv_validator_key.json` we're interested in
+    #[derive(Deserialize)]
+    struct PrivValidatorKey {
+        address: account::Id,
+    }
+
+    let json = match fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let key = serde_json::from_str::<PrivValidatorKey>(&json).map_err(|e| {
+        format_err!(
+            ErrorKind::IoError,
+            "couldn't parse {}: {}",
+            path.display(),
+            e
+        )
+    })?;
+
+    Ok(Some(key.address))
+}
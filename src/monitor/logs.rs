@@ -0,0 +1,291 @@
+//! Node log monitor: tails the node's log file looking for known patterns
+
+use super::{check::Check, message::Message, rpc::RpcClient};
+use crate::{config::agent::LogPatternConfig, prelude::*};
+use async_trait::async_trait;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    os::unix::fs::MetadataExt,
+    path::PathBuf,
+};
+
+/// Maximum number of bytes read from the log in a single update
+pub const MAX_READ_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Maximum length of a log line which is reported to the collector
+pub const MAX_LINE_LEN: usize = 1024;
+
+/// Maximum number of bytes buffered while waiting for the end of a line.
+///
+/// Longer lines are split, so a file without newlines can't grow the buffer
+/// without bound.
+pub const MAX_PARTIAL_LEN: usize = 64 * 1024;
+
+/// Log monitor: tails a log file and reports lines matching any of the
+/// configured patterns.
+///
+/// Tailing starts at the end of the file, and follows the file across
+/// rotations (detected by its device and inode changing) and truncations.
+#[derive(Clone, Debug)]
+pub struct Logs {
+    /// Position in the log file
+    tail: Tail,
+
+    /// Patterns to look for
+    patterns: Vec<Pattern>,
+}
+
+/// Position in a log file being tailed
+#[derive(Clone, Debug)]
+struct Tail {
+    /// Path to the log file
+    path: PathBuf,
+
+    /// Device and inode of the log file and the offset we've read up to
+    position: Option<(u64, u64, u64)>,
+
+    /// Incomplete last line from the previous read
+    partial: Vec<u8>,
+}
+
+/// Compiled log pattern
+#[derive(Clone, Debug)]
+struct Pattern {
+    /// Name of the pattern
+    name: String,
+
+    /// Regex to match log lines against
+    regex: Regex,
+
+    /// Severity of matching lines
+    severity: Severity,
+}
+
+impl Logs {
+    /// Create a new log monitor for the given log file
+    pub fn new(path: impl Into<PathBuf>, patterns: &[LogPatternConfig]) -> Result<Self, Error> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                Ok(Pattern {
+                    name: pattern.name.clone(),
+                    regex: Regex::new(&pattern.regex).map_err(|e| {
+                        format_err!(
+                            ErrorKind::ConfigError,
+                            "invalid log pattern {}: {}",
+                            &pattern.name,
+                            e
+                        )
+                    })?,
+                    severity: pattern.severity,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self {
+            tail: Tail {
+                path: path.into(),
+                position: None,
+                partial: vec![],
+            },
+            patterns,
+        })
+    }
+
+    /// Read complete lines which have been appended to the log since the
+    /// last update.
+    ///
+    /// The file is read on a blocking thread, which is handed a copy of the
+    /// tail position and returns it updated.
+    async fn read_lines(&mut self) -> Result<Vec<String>, Error> {
+        let mut tail = self.tail.clone();
+
+        let (tail, lines) = tokio::task::spawn_blocking(move || {
+            let lines = tail.read_lines();
+            (tail, lines)
+        })
+        .await
+        .map_err(|e| format_err!(ErrorKind::IoError, "log read failed: {}", e))?;
+
+        self.tail = tail;
+        Ok(lines?)
+    }
+}
+
+impl Tail {
+    /// Read complete lines which have been appended to the log since the
+    /// last read
+    fn read_lines(&mut self) -> Result<Vec<String>, io::Error> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            // The log may be in the middle of being rotated
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
+
+        let metadata = file.metadata()?;
+        let (dev, inode) = (metadata.dev(), metadata.ino());
+
+        let offset = match self.position {
+            Some((last_dev, last_inode, offset))
+                if last_dev == dev && last_inode == inode && offset <= metadata.len() =>
+            {
+                offset
+            }
+            // The log was rotated or truncated: start from the beginning
+            Some(_) => {
+                self.partial.clear();
+                0
+            }
+            // Don't report anything which was logged before we started
+            None => metadata.len(),
+        };
+
+        file.seek(SeekFrom::Start(offset))?;
+
+        let mut buf = vec![];
+        file.take(MAX_READ_BYTES).read_to_end(&mut buf)?;
+        self.position = Some((dev, inode, offset + buf.len() as u64));
+        self.partial.extend_from_slice(&buf);
+
+        let mut lines = vec![];
+
+        while let Some(pos) = self.partial.iter().position(|&byte| byte == b'\n') {
+            let line = self.partial.drain(..=pos).collect::<Vec<u8>>();
+            lines.push(String::from_utf8_lossy(&line).trim_end().to_owned());
+        }
+
+        if self.partial.len() > MAX_PARTIAL_LEN {
+            lines.push(String::from_utf8_lossy(&self.partial).into_owned());
+            self.partial.clear();
+        }
+
+        Ok(lines)
+    }
+}
+
+#[async_trait]
+impl Check for Logs {
+    async fn update(
+        &mut self,
+        _rpc_client: &RpcClient,
+        _force: bool,
+    ) -> Result<Vec<Message>, Error> {
+        let lines = self.read_lines().await?;
+        let mut events: Vec<LogEvent> = vec![];
+
+        // Report at most one event per pattern for each update, along with
+        // the number of lines which matched it
+        for line in &lines {
+            for pattern in &self.patterns {
+                if !pattern.regex.is_match(line) {
+                    continue;
+                }
+
+                match events
+                    .iter_mut()
+                    .find(|event| event.pattern == pattern.name)
+                {
+                    Some(event) => event.count += 1,
+                    None => events.push(LogEvent::new(pattern, line)),
+                }
+            }
+        }
+
+        Ok(events.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Log lines matching a pattern
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LogEvent {
+    /// Name of the pattern which matched
+    pub pattern: String,
+
+    /// Severity of the pattern
+    pub severity: Severity,
+
+    /// First matching log line
+    pub line: String,
+
+    /// Number of matching log lines
+    pub count: usize,
+}
+
+impl LogEvent {
+    /// Create a new log event for a line matching the given pattern
+    fn new(pattern: &Pattern, line: &str) -> Self {
+        let mut line = line.to_owned();
+
+        if line.len() > MAX_LINE_LEN {
+            let mut end = MAX_LINE_LEN;
+
+            while !line.is_char_boundary(end) {
+                end -= 1;
+            }
+
+            line.truncate(end);
+        }
+
+        Self {
+            pattern: pattern.name.clone(),
+            severity: pattern.severity,
+            line,
+            count: 1,
+        }
+    }
+}
+
+/// Severity of a log pattern
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Severity {
+    /// Informational
+    #[serde(rename = "info")]
+    Info,
+
+    /// Warning
+    #[serde(rename = "warning")]
+    Warning,
+
+    /// Critical (paged)
+    #[serde(rename = "critical")]
+    Critical,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, fs, io::Write};
+
+    #[tokio::test]
+    async fn tails_log_file() {
+        let path = env::temp_dir().join(format!("cosmon-logs-test-{}.log", std::process::id()));
+        fs::write(&path, "panic: old news\n").unwrap();
+
+        let patterns = [LogPatternConfig {
+            name: "panic".to_owned(),
+            regex: "panic".to_owned(),
+            severity: Severity::Critical,
+        }];
+
+        let mut logs = Logs::new(&path, &patterns).unwrap();
+        assert!(logs.read_lines().await.unwrap().is_empty());
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"I[1] executed block\npanic: runtime err")
+            .unwrap();
+        assert_eq!(logs.read_lines().await.unwrap(), ["I[1] executed block"]);
+
+        file.write_all(b"or\n").unwrap();
+        assert_eq!(logs.read_lines().await.unwrap(), ["panic: runtime error"]);
+
+        // Truncation starts over from the beginning of the file
+        fs::write(&path, "E[2] dialing failed\n").unwrap();
+        assert_eq!(logs.read_lines().await.unwrap(), ["E[2] dialing failed"]);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,90 @@
+//! Mempool monitor
+
+use super::{message::Message, rpc::RpcClient};
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use tendermint_config::MempoolConfig;
+
+/// Mempool monitor: monitors the `/num_unconfirmed_txs` RPC endpoint.
+#[derive(Clone, Debug)]
+pub struct Mempool {
+    /// Maximum number of transactions in the mempool (`mempool.size`)
+    max_txs: u64,
+
+    /// Maximum total size of the mempool in bytes (`mempool.max_txs_bytes`)
+    max_bytes: u64,
+}
+
+impl Mempool {
+    /// Create a new mempool monitor for a node with the given mempool config
+    pub fn new(config: &MempoolConfig) -> Self {
+        Self {
+            max_txs: config.size,
+            max_bytes: config.max_txs_bytes,
+        }
+    }
+
+    /// Query the mempool using the given RPC client, returning its status
+    pub async fn update(&mut self, rpc_client: &RpcClient) -> Result<Vec<Message>, Error> {
+        let txs = rpc_client
+            .get::<NumUnconfirmedTxs>("/num_unconfirmed_txs")
+            .await?;
+
+        Ok(vec![MempoolStatus {
+            n_txs: txs.n_txs,
+            total_bytes: txs.total_bytes,
+            max_txs: self.max_txs,
+            max_bytes: self.max_bytes,
+        }
+        .into()])
+    }
+}
+
+/// Response from the `/num_unconfirmed_txs` endpoint
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct NumUnconfirmedTxs {
+    /// Number of transactions in the mempool
+    #[serde(with = "tendermint::serializers::from_str")]
+    n_txs: u64,
+
+    /// Total size of the transactions in the mempool in bytes
+    #[serde(with = "tendermint::serializers::from_str")]
+    total_bytes: u64,
+}
+
+impl tendermint_rpc::Response for NumUnconfirmedTxs {}
+
+/// Mempool status
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MempoolStatus {
+    /// Number of transactions in the mempool
+    pub n_txs: u64,
+
+    /// Total size of the transactions in the mempool in bytes
+    pub total_bytes: u64,
+
+    /// Maximum number of transactions in the mempool
+    pub max_txs: u64,
+
+    /// Maximum total size of the mempool in bytes
+    pub max_bytes: u64,
+}
+
+impl MempoolStatus {
+    /// Fraction of the mempool's capacity which is in use, by either
+    /// transaction count or size (whichever is higher)
+    pub fn fill_ratio(&self) -> f64 {
+        let txs_ratio = ratio(self.n_txs, self.max_txs);
+        let bytes_ratio = ratio(self.total_bytes, self.max_bytes);
+        txs_ratio.max(bytes_ratio)
+    }
+}
+
+/// Compute the ratio of `value` to `max` (treating an unknown maximum as 0)
+fn ratio(value: u64, max: u64) -> f64 {
+    if max == 0 {
+        0.0
+    } else {
+        value as f64 / max as f64
+    }
+}
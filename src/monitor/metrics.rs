@@ -0,0 +1,301 @@
+//! Prometheus metrics monitor: scrapes the node's instrumentation endpoint
+
+use super::{check::Check, message::Message, rpc::RpcClient};
+use crate::prelude::*;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Metrics forwarded to the collector when no allowlist is configured
+pub const DEFAULT_ALLOWLIST: &[&str] = &[
+    "consensus_height",
+    "consensus_rounds",
+    "consensus_validators",
+    "consensus_missing_validators",
+    "consensus_byzantine_validators",
+    "consensus_validator_missed_blocks",
+    "consensus_block_interval_seconds",
+    "p2p_peers",
+    "mempool_size",
+    "mempool_failed_txs",
+];
+
+/// Prometheus metrics monitor: scrapes the node's metrics and reports the
+/// allowlisted series.
+#[derive(Clone, Debug)]
+pub struct Metrics {
+    /// URL of the metrics endpoint
+    url: String,
+
+    /// HTTP client
+    http_client: reqwest::Client,
+
+    /// Names of metric families to report (with the namespace prefixed)
+    allowlist: Vec<String>,
+}
+
+impl Metrics {
+    /// Create a new metrics monitor.
+    ///
+    /// Allowlist entries are matched both as-is and with the node's metrics
+    /// namespace (e.g. `tendermint`) prefixed.
+    pub fn new(listen_addr: &str, namespace: &str, allowlist: &[String]) -> Result<Self, Error> {
+        let (host, port) = listen_addr.rsplit_once(':').ok_or_else(|| {
+            format_err!(
+                ErrorKind::ConfigError,
+                "invalid prometheus_listen_addr: {}",
+                listen_addr
+            )
+        })?;
+
+        let host = match host {
+            "" | "0.0.0.0" => "127.0.0.1",
+            "[::]" => "[::1]",
+            other => other,
+        };
+
+        let allowlist = allowlist
+            .iter()
+            .flat_map(|name| vec![name.clone(), format!("{}_{}", namespace, name)])
+            .collect();
+
+        Ok(Self {
+            url: format!("http://{}:{}/metrics", host, port),
+            http_client: reqwest::Client::new(),
+            allowlist,
+        })
+    }
+}
+
+#[async_trait]
+impl Check for Metrics {
+    async fn update(
+        &mut self,
+        _rpc_client: &RpcClient,
+        _force: bool,
+    ) -> Result<Vec<Message>, Error> {
+        let body = self
+            .http_client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format_err!(ErrorKind::HttpError, "{}: {}", &self.url, e))?
+            .text()
+            .await
+            .map_err(|e| format_err!(ErrorKind::HttpError, "{}: {}", &self.url, e))?;
+
+        let metrics = parse(&body)
+            .into_iter()
+            .filter(|metric| self.allowlist.contains(&metric.family))
+            .collect::<Vec<_>>();
+
+        if metrics.is_empty() {
+            Ok(vec![])
+        } else {
+            Ok(vec![metrics.into()])
+        }
+    }
+}
+
+/// Sample of a metric
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Metric {
+    /// Name of the metric family (e.g. `tendermint_consensus_rounds`)
+    pub family: String,
+
+    /// Name of the sample (e.g. `tendermint_consensus_block_interval_seconds_bucket`)
+    pub name: String,
+
+    /// Type of the metric
+    pub kind: MetricKind,
+
+    /// Labels of the sample
+    pub labels: Map<String, String>,
+
+    /// Value of the sample
+    pub value: MetricValue,
+}
+
+/// Type of a metric
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum MetricKind {
+    /// Counter
+    #[serde(rename = "counter")]
+    Counter,
+
+    /// Gauge
+    #[serde(rename = "gauge")]
+    Gauge,
+
+    /// Histogram
+    #[serde(rename = "histogram")]
+    Histogram,
+
+    /// Summary
+    #[serde(rename = "summary")]
+    Summary,
+
+    /// Untyped
+    #[serde(rename = "untyped")]
+    Untyped,
+}
+
+impl MetricKind {
+    /// Parse a metric type from a `# TYPE` line
+    fn parse(s: &str) -> Self {
+        match s {
+            "counter" => MetricKind::Counter,
+            "gauge" => MetricKind::Gauge,
+            "histogram" => MetricKind::Histogram,
+            "summary" => MetricKind::Summary,
+            _ => MetricKind::Untyped,
+        }
+    }
+}
+
+/// Value of a metric sample
+#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+pub struct MetricValue(pub f64);
+
+// Samples are compared bitwise so messages containing them can be `Eq`
+impl PartialEq for MetricValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for MetricValue {}
+
+/// Parse metrics in the Prometheus text exposition format, skipping any
+/// lines which can't be parsed
+pub fn parse(text: &str) -> Vec<Metric> {
+    let mut kinds = Map::new();
+    let mut metrics = vec![];
+
+    for line in text.lines() {
+        let line = line.trim();
+
+        if let Some(type_decl) = line.strip_prefix("# TYPE ") {
+            let mut parts = type_decl.split_whitespace();
+
+            if let (Some(family), Some(kind)) = (parts.next(), parts.next()) {
+                kinds.insert(family.to_owned(), MetricKind::parse(kind));
+            }
+
+            continue;
+        }
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some((name, labels, value)) = parse_sample(line) {
+            let (family, kind) = family_of(&kinds, &name);
+
+            metrics.push(Metric {
+                family,
+                name,
+                kind,
+                labels,
+                value: MetricValue(value),
+            });
+        }
+    }
+
+    metrics
+}
+
+/// Find the family a sample belongs to, accounting for the suffixes of
+/// histogram and summary samples
+fn family_of(kinds: &Map<String, MetricKind>, name: &str) -> (String, MetricKind) {
+    for suffix in &["_bucket", "_sum", "_count"] {
+        if let Some(family) = name.strip_suffix(suffix) {
+            if let Some(kind @ (MetricKind::Histogram | MetricKind::Summary)) = kinds.get(family) {
+                return (family.to_owned(), *kind);
+            }
+        }
+    }
+
+    let kind = kinds.get(name).copied().unwrap_or(MetricKind::Untyped);
+    (name.to_owned(), kind)
+}
+
+/// Parse a sample line: `name{label="value",...} value [timestamp]`
+fn parse_sample(line: &str) -> Option<(String, Map<String, String>, f64)> {
+    let name_end = line.find(|c: char| c == '{' || c.is_whitespace())?;
+    let name = line[..name_end].to_owned();
+    let mut labels = Map::new();
+    let mut rest = &line[name_end..];
+
+    if let Some(mut label_text) = rest.strip_prefix('{') {
+        loop {
+            label_text = label_text.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+
+            if let Some(after_labels) = label_text.strip_prefix('}') {
+                rest = after_labels;
+                break;
+            }
+
+            let (label_name, after_name) = label_text.split_once('=')?;
+            let mut value_chars = after_name.strip_prefix('"')?.char_indices();
+            let mut value = String::new();
+
+            let value_len = loop {
+                match value_chars.next()? {
+                    (i, '"') => break i,
+                    (_, '\\') => match value_chars.next()? {
+                        (_, 'n') => value.push('\n'),
+                        (_, c) => value.push(c),
+                    },
+                    (_, c) => value.push(c),
+                }
+            };
+
+            labels.insert(label_name.trim().to_owned(), value);
+
+            // Skip the opening quote, the value and the closing quote
+            label_text = &after_name[value_len + 2..];
+        }
+    }
+
+    let value = rest.split_whitespace().next()?.parse::<f64>().ok()?;
+    Some((name, labels, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_METRICS: &str = r#"# HELP tendermint_consensus_rounds Number of rounds.
+# TYPE tendermint_consensus_rounds gauge
+tendermint_consensus_rounds{chain_id="cosmoshub-4"} 0
+# TYPE tendermint_consensus_block_interval_seconds histogram
+tendermint_consensus_block_interval_seconds_bucket{chain_id="cosmoshub-4",le="+Inf"} 1234
+tendermint_consensus_block_interval_seconds_sum{chain_id="cosmoshub-4"} 7890.5
+# TYPE tendermint_p2p_peer_receive_bytes_total counter
+tendermint_p2p_peer_receive_bytes_total{chain_id="cosmoshub-4",peer_id="a\"b"} 42
+not a metric
+"#;
+
+    #[test]
+    fn parses_text_format() {
+        let metrics = parse(EXAMPLE_METRICS);
+        assert_eq!(metrics.len(), 4);
+
+        assert_eq!(metrics[0].family, "tendermint_consensus_rounds");
+        assert_eq!(metrics[0].kind, MetricKind::Gauge);
+        assert_eq!(metrics[0].labels["chain_id"], "cosmoshub-4");
+
+        assert_eq!(
+            metrics[1].family,
+            "tendermint_consensus_block_interval_seconds"
+        );
+        assert_eq!(metrics[1].kind, MetricKind::Histogram);
+        assert_eq!(metrics[1].labels["le"], "+Inf");
+        assert_eq!(metrics[2].value, MetricValue(7890.5));
+
+        assert_eq!(metrics[3].kind, MetricKind::Counter);
+        assert_eq!(metrics[3].labels["peer_id"], "a\"b");
+        assert_eq!(metrics[3].value, MetricValue(42.0));
+    }
+}
@@ -1,11 +1,12 @@
 //! Network information monitor
 
-use super::message::Message;
+use super::{message::Message, rpc::RpcClient};
 use crate::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use tendermint::node;
 use tendermint_config::net;
-use tendermint_rpc::{Client, HttpClient};
+use tendermint_rpc::{endpoint::net_info, Client};
 
 /// Map of peer IDs to their peer information
 type PeerMap = Map<node::Id, Peer>;
@@ -21,6 +22,9 @@ pub struct NetInfo {
 
     /// Previous peer list
     peers: Vec<Peer>,
+
+    /// Previous list of anomalous peers
+    anomalous_peers: Vec<AnomalousPeer>,
 }
 
 impl NetInfo {
@@ -30,66 +34,76 @@ impl NetInfo {
             persistent_peers,
             private_peer_ids,
             peers: vec![],
+            anomalous_peers: vec![],
         }
     }
 
     /// Update internal state using the given RPC client, returning any changes
-    // TODO(tarcieri): don't error out on attacker-controlled values; log instead
+    ///
+    /// Peer information is attacker-controlled, so peers which can't be
+    /// processed are recorded as anomalous rather than failing the update.
     pub async fn update(
         &mut self,
-        rpc_client: &HttpClient,
+        rpc_client: &RpcClient,
         force: bool,
     ) -> Result<Vec<Message>, Error> {
         let mut peer_map = self.peer_map()?;
+        let mut seen = BTreeSet::new();
+        let mut anomalous_peers = vec![];
 
         for peer_info in rpc_client.net_info().await?.peers {
             let node_id = &peer_info.node_info.id;
-            let listen_addr = peer_info
-                .node_info
-                .listen_addr
-                .to_string()
-                .parse()
-                .map_err(|err| {
-                    format_err!(
-                        ErrorKind::ConfigError,
-                        "can't parse peer's listen address: {} ({})",
-                        &peer_info.node_info.listen_addr,
-                        err
-                    )
-                })?;
-
-            if let Some(peer) = peer_map.get_mut(node_id) {
-                peer.connection = if peer_info.is_outbound {
-                    ConnectionStatus::Out
-                } else {
-                    ConnectionStatus::In
-                };
-            } else if let net::Address::Tcp { port, .. } = listen_addr {
-                let addr = net::Address::Tcp {
-                    peer_id: Some(*node_id),
-                    host: peer_info.remote_ip.to_string(),
-                    port,
-                };
 
-                let status = if peer_info.is_outbound {
-                    ConnectionStatus::Out
-                } else {
-                    ConnectionStatus::In
-                };
+            let status = if peer_info.is_outbound {
+                ConnectionStatus::Out
+            } else {
+                ConnectionStatus::In
+            };
 
-                let peer = Peer {
-                    addr,
-                    connection: status,
-                    persistent: false,
-                    private: false,
-                };
-                assert_eq!(peer_map.insert(*node_id, peer), None);
+            let stats = PeerStats::from(&peer_info.connection_status);
+
+            let anomaly = if !seen.insert(*node_id) {
+                Some(Anomaly::Duplicate)
+            } else if let Some(peer) = peer_map.get_mut(node_id) {
+                peer.connection = status;
+                peer.stats = Some(stats);
+                None
             } else {
-                fail!(
-                    ErrorKind::ConfigError,
-                    "unsupported peer listen addr: {}",
-                    listen_addr
-                );
+                match peer_info.node_info.listen_addr.to_string().parse() {
+                    Ok(net::Address::Tcp { port, .. }) => {
+                        let addr = net::Address::Tcp {
+                            peer_id: Some(*node_id),
+                            host: peer_info.remote_ip.to_string(),
+                            port,
+                        };
+
+                        let peer = Peer {
+                            addr,
+                            connection: status,
+                            persistent: false,
+                            private: false,
+                            stats: Some(stats),
+                        };
+
+                        peer_map.insert(*node_id, peer);
+                        None
+                    }
+                    Ok(_) => Some(Anomaly::UnsupportedListenAddr),
+                    Err(_) => Some(Anomaly::InvalidListenAddr),
+                }
+            };
+
+            if let Some(anomaly) = anomaly {
+                let anomalous_peer = AnomalousPeer {
+                    id: *node_id,
+                    anomaly,
+                    remote_ip: peer_info.remote_ip.to_string(),
+                    listen_addr: peer_info.node_info.listen_addr.to_string(),
+                    moniker: peer_info.node_info.moniker.to_string(),
+                };
+
+                warn!("anomalous peer: {:?}", anomalous_peer);
+                anomalous_peers.push(anomalous_peer);
             }
         }
 
@@ -97,11 +111,24 @@ impl NetInfo {
 
         let mut output = vec![];
 
-        if peers != self.peers || force {
+        // Connection statistics change constantly, so they're only
+        // reported along with other changes or in full reports
+        let changed = peers.len() != self.peers.len()
+            || peers
+                .iter()
+                .zip(&self.peers)
+                .any(|(peer, last_peer)| !peer.same_connection(last_peer));
+
+        if changed || force {
             self.peers = peers.clone();
             output.push(peers.into());
         }
 
+        if anomalous_peers != self.anomalous_peers || force {
+            self.anomalous_peers = anomalous_peers.clone();
+            output.push(anomalous_peers.into());
+        }
+
         Ok(output)
     }
 
@@ -119,6 +146,7 @@ impl NetInfo {
                     connection: ConnectionStatus::None,
                     persistent: true,
                     private: false,
+                    stats: None,
                 };
 
                 if map.insert(*id, peer).is_some() {
@@ -163,6 +191,160 @@ pub struct Peer {
 
     /// Is this peer marked as being private?
     pub private: bool,
+
+    /// Connection statistics (if connected)
+    pub stats: Option<PeerStats>,
+}
+
+impl Peer {
+    /// Get the node ID of this peer (if known)
+    pub fn id(&self) -> Option<node::Id> {
+        match self.addr {
+            net::Address::Tcp { peer_id, .. } => peer_id,
+            net::Address::Unix { .. } => None,
+        }
+    }
+
+    /// Is the connection to this peer the same as to the other peer,
+    /// ignoring connection statistics?
+    pub fn same_connection(&self, other: &Peer) -> bool {
+        self.addr == other.addr
+            && self.connection == other.connection
+            && self.persistent == other.persistent
+            && self.private == other.private
+    }
+}
+
+/// Statistics about the connection to a peer
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PeerStats {
+    /// How long the connection has been established in seconds
+    pub duration_secs: u64,
+
+    /// Data sent to the peer
+    pub send: Throughput,
+
+    /// Data received from the peer
+    pub recv: Throughput,
+
+    /// Per-channel send queues
+    pub channels: Vec<ChannelStats>,
+}
+
+impl PeerStats {
+    /// Is the send queue of any channel full?
+    pub fn is_saturated(&self) -> bool {
+        self.channels.iter().any(ChannelStats::is_saturated)
+    }
+}
+
+impl<'a> From<&'a net_info::ConnectionStatus> for PeerStats {
+    fn from(status: &'a net_info::ConnectionStatus) -> PeerStats {
+        PeerStats {
+            duration_secs: status.duration.as_secs(),
+            send: Throughput::from(&status.send_monitor),
+            recv: Throughput::from(&status.recv_monitor),
+            channels: status
+                .channels
+                .iter()
+                .map(|channel| ChannelStats {
+                    id: channel.id.value(),
+                    send_queue_size: channel.send_queue_size,
+                    send_queue_capacity: channel.send_queue_capacity,
+                    priority: channel.priority,
+                    recently_sent: channel.recently_sent,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Throughput in one direction of a peer connection
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Throughput {
+    /// Total bytes transferred
+    pub bytes: u64,
+
+    /// Current transfer rate in bytes per second
+    pub cur_rate: u64,
+
+    /// Average transfer rate in bytes per second
+    pub avg_rate: u64,
+
+    /// Peak transfer rate in bytes per second
+    pub peak_rate: u64,
+}
+
+impl<'a> From<&'a net_info::Monitor> for Throughput {
+    fn from(monitor: &'a net_info::Monitor) -> Throughput {
+        Throughput {
+            bytes: monitor.bytes,
+            cur_rate: monitor.cur_rate,
+            avg_rate: monitor.avg_rate,
+            peak_rate: monitor.peak_rate,
+        }
+    }
+}
+
+/// Send queue of a peer connection channel
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ChannelStats {
+    /// Channel ID
+    pub id: u64,
+
+    /// Number of messages in the send queue
+    pub send_queue_size: u64,
+
+    /// Capacity of the send queue
+    pub send_queue_capacity: u64,
+
+    /// Priority of the channel
+    pub priority: u64,
+
+    /// Bytes recently sent on the channel
+    pub recently_sent: u64,
+}
+
+impl ChannelStats {
+    /// Is the send queue full?
+    pub fn is_saturated(&self) -> bool {
+        self.send_queue_capacity > 0 && self.send_queue_size >= self.send_queue_capacity
+    }
+}
+
+/// Peer which couldn't be processed, with the raw values it advertised
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AnomalousPeer {
+    /// Node ID of the peer
+    pub id: node::Id,
+
+    /// What's wrong with the peer
+    pub anomaly: Anomaly,
+
+    /// Remote IP address of the peer
+    pub remote_ip: String,
+
+    /// Listen address advertised by the peer
+    pub listen_addr: String,
+
+    /// Moniker advertised by the peer
+    pub moniker: String,
+}
+
+/// Ways in which a peer can be anomalous
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Anomaly {
+    /// Listen address couldn't be parsed
+    #[serde(rename = "invalid_listen_addr")]
+    InvalidListenAddr,
+
+    /// Listen address isn't a TCP address
+    #[serde(rename = "unsupported_listen_addr")]
+    UnsupportedListenAddr,
+
+    /// Peer is listed more than once
+    #[serde(rename = "duplicate")]
+    Duplicate,
 }
 
 /// Status of the connection
@@ -0,0 +1,202 @@
+//! Disk-backed outbox of message envelopes awaiting delivery to the collector
+
+use crate::{message::Envelope, prelude::*};
+use std::{collections::VecDeque, fs, io, path::PathBuf};
+
+/// File extension used for outbox entries
+const ENTRY_EXTENSION: &str = "json";
+
+/// Outbox: a disk-backed FIFO queue of message envelopes.
+///
+/// Each envelope is stored as an individual file named after a monotonically
+/// increasing sequence number. When the outbox is full the oldest envelopes
+/// are evicted.
+///
+/// The sequence numbers of the entries are indexed in memory when the outbox
+/// is opened, so the directory only needs to be scanned once.
+#[derive(Debug)]
+pub struct Outbox {
+    /// Directory containing the outbox entries
+    dir: PathBuf,
+
+    /// Maximum number of entries to keep
+    max_entries: usize,
+
+    /// Sequence numbers of the entries in the outbox, oldest first
+    entries: VecDeque<u64>,
+
+    /// Sequence number of the next entry
+    next_seq: u64,
+}
+
+impl Outbox {
+    /// Open the outbox in the given directory, creating it if it doesn't
+    /// already exist
+    pub fn open(dir: impl Into<PathBuf>, max_entries: usize) -> Result<Self, Error> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        let mut entries = vec![];
+
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+
+            if path.extension().and_then(|ext| ext.to_str()) != Some(ENTRY_EXTENSION) {
+                continue;
+            }
+
+            if let Some(seq) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse().ok())
+            {
+                entries.push(seq);
+            }
+        }
+
+        entries.sort_unstable();
+        let next_seq = entries.last().map(|seq| seq + 1).unwrap_or_default();
+
+        Ok(Self {
+            dir,
+            max_entries,
+            entries: entries.into(),
+            next_seq,
+        })
+    }
+
+    /// Number of entries in the outbox
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Is the outbox empty?
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Add an envelope to the outbox, evicting the oldest entries if the
+    /// outbox is full
+    pub async fn push(&mut self, envelope: &Envelope) -> Result<(), Error> {
+        let seq = self.next_seq;
+        let path = self.entry_path(seq);
+        let tmp_path = path.with_extension("tmp");
+
+        // Write to a temporary file first so partially written entries are
+        // never picked up
+        tokio::fs::write(&tmp_path, envelope.to_json()).await?;
+        tokio::fs::rename(&tmp_path, &path).await?;
+        self.entries.push_back(seq);
+        self.next_seq += 1;
+
+        if self.entries.len() > self.max_entries {
+            let excess = self.entries.len() - self.max_entries;
+            warn!("outbox full; evicting {} oldest envelope(s)", excess);
+
+            for seq in self.entries.drain(..excess).collect::<Vec<_>>() {
+                remove_entry(self.entry_path(seq)).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the oldest entry in the outbox (if any), along with its sequence
+    /// number
+    pub async fn peek(&mut self) -> Result<Option<(u64, Envelope)>, Error> {
+        while let Some(&seq) = self.entries.front() {
+            let path = self.entry_path(seq);
+
+            let bytes = match tokio::fs::read(&path).await {
+                Ok(bytes) => bytes,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    warn!("outbox entry {} disappeared", path.display());
+                    self.entries.pop_front();
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            match serde_json::from_slice(&bytes) {
+                Ok(envelope) => return Ok(Some((seq, envelope))),
+                Err(e) => {
+                    warn!("discarding corrupt outbox entry {}: {}", path.display(), e);
+                    self.entries.pop_front();
+                    remove_entry(path).await?;
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Remove an entry from the outbox (i.e. after it has been delivered)
+    pub async fn remove(&mut self, seq: u64) -> Result<(), Error> {
+        self.entries.retain(|entry| *entry != seq);
+        remove_entry(self.entry_path(seq)).await
+    }
+
+    /// Path to the entry with the given sequence number
+    fn entry_path(&self, seq: u64) -> PathBuf {
+        self.dir.join(format!("{:020}.{}", seq, ENTRY_EXTENSION))
+    }
+}
+
+/// Remove an entry's file, ignoring entries which are already gone
+async fn remove_entry(path: PathBuf) -> Result<(), Error> {
+    match tokio::fs::remove_file(&path).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{message::Message, monitor::mempool::MempoolStatus};
+    use std::env;
+
+    fn example_envelope(n_txs: u64) -> Envelope {
+        let msg = Message::from(MempoolStatus {
+            n_txs,
+            total_bytes: 0,
+            max_txs: 5000,
+            max_bytes: 1_073_741_824,
+        });
+
+        Envelope::new(
+            "cosmoshub-4".parse().unwrap(),
+            "abd636b766dcefb5322d8ca40011ec2cb35efbc2".parse().unwrap(),
+            vec![msg],
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn evicts_oldest_entries() {
+        let dir = env::temp_dir().join(format!("cosmon-outbox-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut outbox = Outbox::open(&dir, 2).unwrap();
+
+        for n_txs in 1..=3 {
+            outbox.push(&example_envelope(n_txs)).await.unwrap();
+        }
+
+        let (seq, envelope) = outbox.peek().await.unwrap().unwrap();
+        assert_eq!(envelope.msg, example_envelope(2).msg);
+        outbox.remove(seq).await.unwrap();
+
+        // Sequence numbers are preserved across reopening the outbox
+        let mut outbox = Outbox::open(&dir, 2).unwrap();
+        outbox.push(&example_envelope(4)).await.unwrap();
+
+        assert_eq!(outbox.len(), 2);
+
+        let (_, envelope) = outbox.peek().await.unwrap().unwrap();
+        assert_eq!(envelope.msg, example_envelope(3).msg);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
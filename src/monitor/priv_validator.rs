@@ -0,0 +1,118 @@
+//! Validator signing state monitor (`priv_validator_state.json`)
+
+use super::{check::Check, message::Message, rpc::RpcClient};
+use crate::prelude::*;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::PathBuf};
+use tendermint::account;
+
+/// Validator signing state monitor: reports the last height/round/step the
+/// node's file-based signer has signed at.
+///
+/// When a remote signer is used the state file isn't updated by the node,
+/// so nothing is reported if it doesn't exist.
+#[derive(Clone, Debug)]
+pub struct PrivValidator {
+    /// Path to `priv_validator_state.json`
+    path: PathBuf,
+
+    /// Address of the node's validator key
+    validator: account::Id,
+
+    /// Last reported signing state
+    state: Option<PrivValidatorState>,
+}
+
+impl PrivValidator {
+    /// Create a new signing state monitor for the given state file
+    pub fn new(path: impl Into<PathBuf>, validator: account::Id) -> Self {
+        Self {
+            path: path.into(),
+            validator,
+            state: None,
+        }
+    }
+
+    /// Read the current signing state (if the state file exists)
+    fn read_state(&self) -> Result<Option<PrivValidatorState>, Error> {
+        let json = match fs::read_to_string(&self.path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let file = serde_json::from_str::<StateFile>(&json).map_err(|e| {
+            format_err!(
+                ErrorKind::IoError,
+                "couldn't parse {}: {}",
+                self.path.display(),
+                e
+            )
+        })?;
+
+        Ok(Some(PrivValidatorState {
+            validator: self.validator,
+            height: file.height,
+            round: file.round,
+            step: file.step,
+        }))
+    }
+}
+
+#[async_trait]
+impl Check for PrivValidator {
+    async fn update(
+        &mut self,
+        _rpc_client: &RpcClient,
+        force: bool,
+    ) -> Result<Vec<Message>, Error> {
+        let mut output = vec![];
+
+        if let Some(state) = self.read_state()? {
+            if self.state.as_ref() != Some(&state) || force {
+                output.push(state.clone().into());
+                self.state = Some(state);
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+/// Contents of `priv_validator_state.json`
+#[derive(Deserialize)]
+struct StateFile {
+    /// Last signed height
+    #[serde(with = "tendermint::serializers::from_str")]
+    height: u64,
+
+    /// Last signed round
+    round: u32,
+
+    /// Last signed step
+    step: u8,
+}
+
+/// Last height/round/step signed by a node's validator key
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PrivValidatorState {
+    /// Address of the validator key
+    pub validator: account::Id,
+
+    /// Last signed height
+    pub height: u64,
+
+    /// Last signed round
+    pub round: u32,
+
+    /// Last signed step (1 = propose, 2 = prevote, 3 = precommit)
+    pub step: u8,
+}
+
+impl PrivValidatorState {
+    /// Has this state advanced past the given earlier state?
+    pub fn is_ahead_of(&self, other: &PrivValidatorState) -> bool {
+        (self.height, self.round, self.step) > (other.height, other.round, other.step)
+    }
+}
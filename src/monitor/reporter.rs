@@ -0,0 +1,188 @@
+//! Reporter: delivers message envelopes to the collector
+
+use super::outbox::Outbox;
+use crate::{config, message::Envelope, prelude::*};
+use rand::Rng;
+use std::time::Duration;
+use tokio::{
+    sync::mpsc,
+    time::{self, Instant},
+};
+
+/// Initial delay before retrying delivery after a failure
+pub const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Maximum delay between delivery attempts
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// Reporter: queues message envelopes in the outbox and delivers them to the
+/// collector in order, retrying with exponential backoff while the
+/// collector is unreachable.
+pub struct Reporter {
+    /// Collector address
+    collector_addr: config::agent::CollectorAddr,
+
+    /// HTTP client
+    http_client: reqwest::Client,
+
+    /// Outbox of undelivered envelopes
+    outbox: Outbox,
+
+    /// Number of consecutive failed delivery attempts
+    failures: u32,
+}
+
+impl Reporter {
+    /// Create a new reporter from the agent configuration
+    pub fn new(agent_config: &config::agent::Config) -> Result<Self, Error> {
+        let outbox = Outbox::open(agent_config.outbox_dir()?, agent_config.outbox.max_entries)?;
+
+        Ok(Self {
+            collector_addr: agent_config.collector.clone(),
+            http_client: reqwest::Client::new(),
+            outbox,
+            failures: 0,
+        })
+    }
+
+    /// Run the reporter, delivering envelopes received on the given channel
+    pub async fn run(mut self, mut envelopes: mpsc::UnboundedReceiver<Envelope>) {
+        // Deliver anything left over in the outbox from a previous run
+        let mut retry_at = self.drain().await;
+
+        loop {
+            let received = match retry_at {
+                Some(at) => tokio::select! {
+                    envelope = envelopes.recv() => Some(envelope),
+                    _ = time::sleep_until(at) => None,
+                },
+                None => Some(envelopes.recv().await),
+            };
+
+            match received {
+                Some(Some(envelope)) => {
+                    if let Err(e) = self.outbox.push(&envelope).await {
+                        status_err!("couldn't add envelope to outbox: {}", e);
+                    }
+                }
+                Some(None) => {
+                    info!("all monitors have stopped; shutting down reporter");
+                    return;
+                }
+                // Backoff period has elapsed
+                None => retry_at = None,
+            }
+
+            if retry_at.is_none() {
+                retry_at = self.drain().await;
+            }
+        }
+    }
+
+    /// Deliver envelopes from the outbox in order until it is empty,
+    /// returning when to retry if delivery failed
+    async fn drain(&mut self) -> Option<Instant> {
+        loop {
+            let (seq, envelope) = match self.outbox.peek().await {
+                Ok(Some(entry)) => entry,
+                Ok(None) => {
+                    self.failures = 0;
+                    return None;
+                }
+                Err(e) => {
+                    status_err!("couldn't read outbox: {}", e);
+                    return Some(self.backoff());
+                }
+            };
+
+            match self.report(&envelope).await {
+                Ok(()) => (),
+                Err(Failure::Retryable(e)) => {
+                    let retry_at = self.backoff();
+                    warn!(
+                        "{}; retrying in {:?}",
+                        e,
+                        retry_at.saturating_duration_since(Instant::now())
+                    );
+                    return Some(retry_at);
+                }
+                // Retrying won't help: drop the envelope so it doesn't block
+                // delivery of the ones queued behind it
+                Err(Failure::Permanent(e)) => {
+                    status_err!("collector rejected envelope {}; dropping it: {}", seq, e);
+                }
+            }
+
+            if let Err(e) = self.outbox.remove(seq).await {
+                status_err!("couldn't remove delivered envelope from outbox: {}", e);
+                return Some(self.backoff());
+            }
+
+            self.failures = 0;
+        }
+    }
+
+    /// Record a failed delivery attempt, computing when to retry using
+    /// exponential backoff with jitter
+    fn backoff(&mut self) -> Instant {
+        let delay = INITIAL_RETRY_DELAY
+            .checked_mul(1 << self.failures.min(16))
+            .unwrap_or(MAX_RETRY_DELAY)
+            .min(MAX_RETRY_DELAY);
+
+        self.failures = self.failures.saturating_add(1);
+
+        // Randomize the second half of the delay so agents don't retry in
+        // lockstep after a collector outage
+        let half = delay / 2;
+        let jitter = rand::thread_rng().gen_range(Duration::default()..=half);
+        Instant::now() + half + jitter
+    }
+
+    /// Deliver a message envelope to the collector
+    async fn report(&self, msg: &Envelope) -> Result<(), Failure> {
+        let url = match &self.collector_addr {
+            config::agent::CollectorAddr::Http(config::agent::HttpConfig { uri }) => {
+                format!("{}/collector", uri)
+            }
+        };
+
+        let res = self
+            .http_client
+            .post(&url)
+            .body(msg.to_json())
+            .send()
+            .await
+            .map_err(|e| Failure::Retryable(format_err!(ErrorKind::ReportError, "{}", e).into()))?;
+
+        res.error_for_status().map_err(|e| {
+            let permanent = e.status().map(is_permanent).unwrap_or(false);
+            let err = format_err!(ErrorKind::ReportError, "{}", e).into();
+
+            if permanent {
+                Failure::Permanent(err)
+            } else {
+                Failure::Retryable(err)
+            }
+        })?;
+
+        Ok(())
+    }
+}
+
+/// Failure to deliver a message envelope
+enum Failure {
+    /// Connection failures and server errors: delivery may succeed later
+    Retryable(Error),
+
+    /// The collector rejected the envelope: delivery will never succeed
+    Permanent(Error),
+}
+
+/// Does the given HTTP status mean the collector will never accept the
+/// envelope, however many times it's retried?
+fn is_permanent(status: reqwest::StatusCode) -> bool {
+    status.is_client_error()
+        && status != reqwest::StatusCode::REQUEST_TIMEOUT
+        && status != reqwest::StatusCode::TOO_MANY_REQUESTS
+}
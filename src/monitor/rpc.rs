@@ -0,0 +1,233 @@
+//! RPC client for Tendermint nodes reachable over TCP or Unix domain sockets
+
+use crate::{net, prelude::*};
+use async_trait::async_trait;
+use std::{io, path::PathBuf};
+use tendermint_rpc::{
+    endpoint::abci_query::AbciQuery, Client, HttpClient, Response, SimpleRequest,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::UnixStream,
+};
+
+/// Codespace of errors defined by the Cosmos SDK itself
+pub const SDK_CODESPACE: &str = "sdk";
+
+/// Code of the Cosmos SDK's `ErrKeyNotFound`, which gRPC `NotFound` errors
+/// are mapped to when queried over ABCI
+const SDK_KEY_NOT_FOUND: u32 = 38;
+
+/// Perform an ABCI query for a gRPC method of a Cosmos SDK application,
+/// returning `None` if the application reports the queried item doesn't exist
+pub async fn abci_query<C, Req, Res>(
+    client: &C,
+    path: &str,
+    request: Req,
+) -> Result<Option<Res>, Error>
+where
+    C: Client + Sync,
+    Req: prost::Message,
+    Res: prost::Message + Default,
+{
+    let response = client
+        .abci_query(Some(path.to_owned()), request.encode_to_vec(), None, false)
+        .await?;
+
+    decode_abci_response(path, response)
+}
+
+/// Decode the response to an ABCI query for a gRPC method of a Cosmos SDK
+/// application, returning `None` if the application reports the queried item
+/// doesn't exist
+pub fn decode_abci_response<Res>(path: &str, response: AbciQuery) -> Result<Option<Res>, Error>
+where
+    Res: prost::Message + Default,
+{
+    if response.code.is_err() {
+        if response.codespace == SDK_CODESPACE && response.code.value() == SDK_KEY_NOT_FOUND {
+            return Ok(None);
+        }
+
+        fail!(ErrorKind::RpcError, "{} failed: {}", path, response.log);
+    }
+
+    Ok(Some(Res::decode(response.value.as_slice()).map_err(
+        |e| {
+            format_err!(
+                ErrorKind::RpcError,
+                "couldn't decode {} response: {}",
+                path,
+                e
+            )
+        },
+    )?))
+}
+
+/// RPC client for a Tendermint node.
+///
+/// Implements [`tendermint_rpc::Client`] regardless of which transport is
+/// used to talk to the node.
+#[allow(clippy::large_enum_variant)] // only a handful of clients exist per agent
+#[derive(Clone, Debug)]
+pub enum RpcClient {
+    /// HTTP over TCP
+    Http {
+        /// HTTP client
+        client: HttpClient,
+
+        /// HTTP client for endpoints not supported by `tendermint-rpc`
+        http_client: reqwest::Client,
+
+        /// Host and port of the RPC server (e.g. `127.0.0.1:26657`)
+        authority: String,
+    },
+
+    /// HTTP over a Unix domain socket
+    Unix(UnixClient),
+}
+
+impl RpcClient {
+    /// Create a new RPC client for the node at the given address
+    pub fn new(addr: &net::Address) -> Result<Self, Error> {
+        match addr {
+            net::Address::Tcp { host, port, .. } => {
+                // IPv6 addresses need to be bracketed to be used in URLs
+                let authority = if host.contains(':') && !host.starts_with('[') {
+                    format!("[{}]:{}", host, port)
+                } else {
+                    format!("{}:{}", host, port)
+                };
+
+                let client = HttpClient::new(format!("http://{}", authority).as_str())?;
+
+                Ok(RpcClient::Http {
+                    client,
+                    http_client: reqwest::Client::new(),
+                    authority,
+                })
+            }
+            net::Address::Unix { path } => Ok(RpcClient::Unix(UnixClient::new(path.clone()))),
+        }
+    }
+
+    /// URL of the node's WebSocket endpoint, if it's reachable over TCP
+    pub fn websocket_url(&self) -> Option<String> {
+        match self {
+            RpcClient::Http { authority, .. } => Some(format!("ws://{}/websocket", authority)),
+            RpcClient::Unix(_) => None,
+        }
+    }
+
+    /// Perform a `GET` request against an RPC endpoint which isn't otherwise
+    /// supported by `tendermint-rpc` (e.g. `/num_unconfirmed_txs`)
+    pub async fn get<R: Response>(&self, path: &str) -> Result<R, Error> {
+        let body = match self {
+            RpcClient::Http {
+                http_client,
+                authority,
+                ..
+            } => http_client
+                .get(format!("http://{}{}", authority, path))
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| format_err!(ErrorKind::RpcError, "{}", e))?
+                .bytes()
+                .await
+                .map_err(|e| format_err!(ErrorKind::RpcError, "{}", e))?
+                .to_vec(),
+            RpcClient::Unix(client) => client.request("GET", path, "").await?,
+        };
+
+        Ok(R::from_string(&body)?)
+    }
+}
+
+#[async_trait]
+impl Client for RpcClient {
+    async fn perform<R>(&self, request: R) -> Result<R::Response, tendermint_rpc::Error>
+    where
+        R: SimpleRequest,
+    {
+        match self {
+            RpcClient::Http { client, .. } => client.perform(request).await,
+            RpcClient::Unix(client) => client.perform(request).await,
+        }
+    }
+}
+
+/// Minimal HTTP/1.0 client for Tendermint RPC servers listening on a Unix
+/// domain socket (i.e. `laddr = "unix://..."`)
+#[derive(Clone, Debug)]
+pub struct UnixClient {
+    /// Path to the Unix domain socket
+    path: PathBuf,
+}
+
+impl UnixClient {
+    /// Create a new client for the given socket path
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Perform a JSON-RPC request
+    pub async fn perform<R>(&self, request: R) -> Result<R::Response, tendermint_rpc::Error>
+    where
+        R: SimpleRequest,
+    {
+        let response = self
+            .request("POST", "/", &request.into_json())
+            .await
+            .map_err(|e| tendermint_rpc::Error::client_internal(e.to_string()))?;
+
+        R::Response::from_string(response)
+    }
+
+    /// Send an HTTP request over the socket, returning the response body.
+    ///
+    /// HTTP/1.0 is used so the server delimits the response body by closing
+    /// the connection rather than using chunked encoding.
+    async fn request(&self, method: &str, path: &str, body: &str) -> Result<Vec<u8>, io::Error> {
+        let mut stream = UnixStream::connect(&self.path).await?;
+
+        let request = format!(
+            "{} {} HTTP/1.0\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            body.len(),
+            body
+        );
+
+        stream.write_all(request.as_bytes()).await?;
+
+        let mut response = vec![];
+        stream.read_to_end(&mut response).await?;
+
+        let body_start = response
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")
+            .map(|pos| pos + 4)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed HTTP response"))?;
+
+        // Status line, e.g. `HTTP/1.0 200 OK`
+        let status_line = response
+            .split(|&byte| byte == b'\r')
+            .next()
+            .map(String::from_utf8_lossy)
+            .unwrap_or_default();
+
+        match status_line.split(' ').nth(1) {
+            Some(code) if code.starts_with('2') => (),
+            _ => {
+                return Err(io::Error::other(format!(
+                    "HTTP error from {}: {}",
+                    self.path.display(),
+                    status_line
+                )))
+            }
+        }
+
+        Ok(response.split_off(body_start))
+    }
+}
@@ -0,0 +1,256 @@
+//! Check scheduler: runs each check concurrently at its own interval
+
+use super::{check::Check, message::Message, rpc::RpcClient};
+use crate::{config::agent::CheckConfig, message::Envelope, net, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tendermint::{chain, node};
+use tokio::{
+    sync::{mpsc, watch, Mutex},
+    task::JoinHandle,
+    time::{self, MissedTickBehavior},
+};
+
+/// Default amount of time a single run of a check may take
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Check scheduler for a particular node
+#[derive(Clone, Debug)]
+pub struct Scheduler {
+    /// RPC address of the node (used in log messages)
+    rpc_addr: net::Address,
+
+    /// RPC client shared by all checks
+    rpc_client: RpcClient,
+
+    /// Chain ID of the node
+    network: chain::Id,
+
+    /// ID of the node
+    node_id: node::Id,
+
+    /// Scheduling config for individual checks
+    checks: Map<String, CheckConfig>,
+
+    /// Interval after which checks report their full state
+    full_report_interval: Duration,
+
+    /// Channel to the reporter which delivers messages to the collector
+    reporter: mpsc::UnboundedSender<Envelope>,
+}
+
+impl Scheduler {
+    /// Create a new scheduler for the given node
+    pub fn new(
+        rpc_addr: net::Address,
+        rpc_client: RpcClient,
+        network: chain::Id,
+        node_id: node::Id,
+        checks: Map<String, CheckConfig>,
+        full_report_interval: Duration,
+        reporter: mpsc::UnboundedSender<Envelope>,
+    ) -> Self {
+        Self {
+            rpc_addr,
+            rpc_client,
+            network,
+            node_id,
+            checks,
+            full_report_interval,
+            reporter,
+        }
+    }
+
+    /// Spawn a task which runs the given check at its configured interval
+    /// (or the given default interval if none has been configured)
+    pub fn spawn<C>(
+        &self,
+        name: &str,
+        default_interval: Duration,
+        check: Arc<Mutex<C>>,
+    ) -> JoinHandle<()>
+    where
+        C: Check + 'static,
+    {
+        let config = self.checks.get(name).cloned().unwrap_or_default();
+        self.spawn_with_config(name, &config, default_interval, check)
+    }
+
+    /// Spawn a task which runs a check whose changes are also reported from
+    /// event subscriptions.
+    ///
+    /// While `subscribed` is set the check is only run when a full report is
+    /// due, and interval polling resumes when the subscription drops.
+    pub fn spawn_event_driven<C>(
+        &self,
+        name: &str,
+        default_interval: Duration,
+        check: Arc<Mutex<C>>,
+        subscribed: watch::Receiver<bool>,
+    ) -> JoinHandle<()>
+    where
+        C: Check + 'static,
+    {
+        let config = self.checks.get(name).cloned().unwrap_or_default();
+        self.spawn_task(name, &config, default_interval, check, Some(subscribed))
+    }
+
+    /// Spawn a task which runs the given check using the given config
+    pub fn spawn_with_config<C>(
+        &self,
+        name: &str,
+        config: &CheckConfig,
+        default_interval: Duration,
+        check: Arc<Mutex<C>>,
+    ) -> JoinHandle<()>
+    where
+        C: Check + 'static,
+    {
+        self.spawn_task(name, config, default_interval, check, None)
+    }
+
+    /// Spawn a task which runs the given check, pausing interval polling
+    /// while `subscribed` is set (if given)
+    fn spawn_task<C>(
+        &self,
+        name: &str,
+        config: &CheckConfig,
+        default_interval: Duration,
+        check: Arc<Mutex<C>>,
+        subscribed: Option<watch::Receiver<bool>>,
+    ) -> JoinHandle<()>
+    where
+        C: Check + 'static,
+    {
+        let name = name.to_owned();
+        let interval = config.interval(default_interval);
+        let timeout = config.timeout(DEFAULT_TIMEOUT);
+        let scheduler = self.clone();
+
+        tokio::spawn(async move {
+            scheduler
+                .run(&name, interval, timeout, check, subscribed)
+                .await;
+        })
+    }
+
+    /// Send the given messages to the collector (if there are any)
+    pub fn send(&self, msg: Vec<Message>) {
+        if let Some(env) = Envelope::new(self.network.clone(), self.node_id, msg) {
+            if self.reporter.send(env).is_err() {
+                status_err!("reporter stopped; dropping messages");
+            }
+        }
+    }
+
+    /// Run the given check forever
+    async fn run<C: Check>(
+        &self,
+        name: &str,
+        interval: Duration,
+        timeout: Duration,
+        check: Arc<Mutex<C>>,
+        subscribed: Option<watch::Receiver<bool>>,
+    ) {
+        let mut ticker = time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let mut last_full_report: Option<Instant> = None;
+        let mut consecutive_failures = 0;
+
+        loop {
+            ticker.tick().await;
+
+            let force = match last_full_report {
+                Some(reported_at) => reported_at.elapsed() >= self.full_report_interval,
+                None => true,
+            };
+
+            // Changes are being reported from event subscriptions
+            if !force && subscribed.as_ref().map(|rx| *rx.borrow()).unwrap_or(false) {
+                continue;
+            }
+
+            let result = {
+                let mut check = check.lock().await;
+
+                match time::timeout(timeout, check.update(&self.rpc_client, force)).await {
+                    Ok(result) => result,
+                    Err(_) => Err(format_err!(
+                        ErrorKind::RpcError,
+                        "timed out after {}s",
+                        timeout.as_secs()
+                    )
+                    .into()),
+                }
+            };
+
+            match result {
+                Ok(mut messages) => {
+                    if force {
+                        last_full_report = Some(Instant::now());
+                    }
+
+                    if consecutive_failures > 0 {
+                        info!("[{}] {} check recovered", &self.rpc_addr, name);
+                        consecutive_failures = 0;
+                        messages.push(CheckStatus::ok(name).into());
+                    }
+
+                    self.send(messages);
+                }
+                Err(e) => {
+                    consecutive_failures += 1;
+                    warn!("[{}] {} check failed: {}", &self.rpc_addr, name, e);
+                    self.send(vec![CheckStatus::failed(
+                        name,
+                        e.to_string(),
+                        consecutive_failures,
+                    )
+                    .into()]);
+                }
+            }
+        }
+    }
+}
+
+/// Status of a check, reported when it fails and when it recovers
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CheckStatus {
+    /// Name of the check
+    pub check: String,
+
+    /// Error which caused the check to fail (if it failed)
+    pub error: Option<String>,
+
+    /// Number of times in a row the check has failed
+    pub consecutive_failures: u64,
+}
+
+impl CheckStatus {
+    /// Status of a check which has succeeded
+    pub fn ok(check: &str) -> Self {
+        Self {
+            check: check.to_owned(),
+            error: None,
+            consecutive_failures: 0,
+        }
+    }
+
+    /// Status of a check which has failed
+    pub fn failed(check: &str, error: String, consecutive_failures: u64) -> Self {
+        Self {
+            check: check.to_owned(),
+            error: Some(error),
+            consecutive_failures,
+        }
+    }
+
+    /// Is the check currently failing?
+    pub fn is_failing(&self) -> bool {
+        self.error.is_some()
+    }
+}
@@ -0,0 +1,198 @@
+//! Node settings monitor: fingerprints `config.toml` and `app.toml` and
+//! extracts key settings so drift between nodes can be detected
+
+use super::{check::Check, message::Message, rpc::RpcClient};
+use crate::prelude::*;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Settings which are expected to differ between nodes (e.g. listen
+/// addresses, or the peering of validators vs. sentries) and are therefore
+/// excluded from the fingerprint
+pub const NODE_SPECIFIC_SETTINGS: &[&str] = &[
+    "app.api.address",
+    "app.grpc.address",
+    "app.grpc-web.address",
+    "config.instrumentation.prometheus_listen_addr",
+    "config.moniker",
+    "config.p2p.external_address",
+    "config.p2p.laddr",
+    "config.p2p.persistent_peers",
+    "config.p2p.pex",
+    "config.p2p.private_peer_ids",
+    "config.p2p.seeds",
+    "config.p2p.unconditional_peer_ids",
+    "config.priv_validator_laddr",
+    "config.proxy_app",
+    "config.rpc.laddr",
+];
+
+/// Key settings which are reported individually
+pub const KEY_SETTINGS: &[&str] = &[
+    "app.pruning",
+    "app.pruning-keep-recent",
+    "app.pruning-interval",
+    "app.minimum-gas-prices",
+    "config.p2p.pex",
+    "config.p2p.seed_mode",
+    "config.p2p.max_num_inbound_peers",
+    "config.p2p.max_num_outbound_peers",
+    "config.instrumentation.prometheus",
+    "config.consensus.timeout_propose",
+    "config.consensus.timeout_prevote",
+    "config.consensus.timeout_precommit",
+    "config.consensus.timeout_commit",
+];
+
+/// Node settings monitor
+#[derive(Clone, Debug)]
+pub struct Settings {
+    /// Path to `config.toml`
+    config_toml_path: PathBuf,
+
+    /// Path to `app.toml`
+    app_toml_path: PathBuf,
+
+    /// Last reported settings
+    last_settings: Option<NodeSettings>,
+}
+
+impl Settings {
+    /// Create a new node settings monitor
+    pub fn new(config_toml_path: impl Into<PathBuf>, app_toml_path: impl Into<PathBuf>) -> Self {
+        Self {
+            config_toml_path: config_toml_path.into(),
+            app_toml_path: app_toml_path.into(),
+            last_settings: None,
+        }
+    }
+
+    /// Load both config files, flattening them into dotted keys
+    fn load(&self) -> Result<Map<String, String>, Error> {
+        let mut settings = Map::new();
+        flatten("config", &load_toml(&self.config_toml_path)?, &mut settings);
+        flatten("app", &load_toml(&self.app_toml_path)?, &mut settings);
+        Ok(settings)
+    }
+}
+
+#[async_trait]
+impl Check for Settings {
+    async fn update(
+        &mut self,
+        _rpc_client: &RpcClient,
+        force: bool,
+    ) -> Result<Vec<Message>, Error> {
+        let node_settings = NodeSettings::from_flattened(&self.load()?);
+        let mut output = vec![];
+
+        if self.last_settings.as_ref() != Some(&node_settings) || force {
+            output.push(node_settings.clone().into());
+            self.last_settings = Some(node_settings);
+        }
+
+        Ok(output)
+    }
+}
+
+/// Fingerprint and key settings of a node's configuration
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NodeSettings {
+    /// SHA-256 of all settings except node-specific ones (hex)
+    pub fingerprint: String,
+
+    /// Values of the key settings (if set)
+    pub settings: Map<String, String>,
+}
+
+impl NodeSettings {
+    /// Compute the fingerprint and extract key settings from the given
+    /// flattened settings
+    pub fn from_flattened(all_settings: &Map<String, String>) -> Self {
+        let mut hasher = Sha256::new();
+
+        for (key, value) in all_settings {
+            if !NODE_SPECIFIC_SETTINGS.contains(&key.as_str()) {
+                hasher.update(format!("{}={}\n", key, value).as_bytes());
+            }
+        }
+
+        let settings = all_settings
+            .iter()
+            .filter(|(key, _)| KEY_SETTINGS.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+
+        Self {
+            fingerprint: format!("{:x}", hasher.finalize()),
+            settings,
+        }
+    }
+}
+
+/// Load a TOML file (if it exists)
+fn load_toml(path: &Path) -> Result<toml::Value, Error> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        // `app.toml` doesn't exist for non-Cosmos SDK applications
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(contents.parse::<toml::Value>().map_err(|e| {
+        format_err!(
+            ErrorKind::ConfigError,
+            "couldn't parse {}: {}",
+            path.display(),
+            e
+        )
+    })?)
+}
+
+/// Flatten a TOML value into dotted keys and normalized string values
+fn flatten(prefix: &str, value: &toml::Value, output: &mut Map<String, String>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                flatten(&format!("{}.{}", prefix, key), value, output);
+            }
+        }
+        toml::Value::String(s) => {
+            output.insert(prefix.to_owned(), s.clone());
+        }
+        other => {
+            output.insert(prefix.to_owned(), other.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(config_toml: &str) -> NodeSettings {
+        let mut flattened = Map::new();
+        flatten("config", &config_toml.parse().unwrap(), &mut flattened);
+        NodeSettings::from_flattened(&flattened)
+    }
+
+    #[test]
+    fn ignores_node_specific_settings() {
+        let a =
+            settings("moniker = \"a\"\n[p2p]\npex = true\n[consensus]\ntimeout_commit = \"5s\"\n");
+        let b =
+            settings("moniker = \"b\"\n[p2p]\npex = false\n[consensus]\ntimeout_commit = \"5s\"\n");
+        let c =
+            settings("moniker = \"c\"\n[p2p]\npex = true\n[consensus]\ntimeout_commit = \"1s\"\n");
+
+        assert_eq!(a.fingerprint, b.fingerprint);
+        assert_ne!(a.fingerprint, c.fingerprint);
+        assert_eq!(b.settings["config.p2p.pex"], "false");
+        assert_eq!(c.settings["config.consensus.timeout_commit"], "1s");
+    }
+}
@@ -0,0 +1,154 @@
+//! Validator signature monitor
+
+use super::{message::Message, rpc::RpcClient};
+use crate::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use tendermint::{
+    account,
+    block::{self, Block, Commit, CommitSig},
+};
+use tendermint_rpc::Client;
+
+/// Maximum number of blocks to fetch in a single update when polling
+pub const MAX_BLOCKS_PER_UPDATE: u64 = 100;
+
+/// Validator signature monitor: records whether the local validator signed
+/// each block by inspecting the `last_commit` of newly committed blocks.
+#[derive(Clone, Debug)]
+pub struct Signing {
+    /// Address of the local validator
+    validator_addr: account::Id,
+
+    /// Height of the last block whose `last_commit` was inspected
+    last_block_height: Option<block::Height>,
+}
+
+impl Signing {
+    /// Create a new signature monitor for the given validator
+    pub fn new(validator_addr: account::Id) -> Self {
+        Self {
+            validator_addr,
+            last_block_height: None,
+        }
+    }
+
+    /// Fetch any blocks committed since the last update using the given RPC
+    /// client, returning the local validator's signatures on them.
+    ///
+    /// Nothing is reported while the node has no voting power.
+    pub async fn update(&mut self, rpc_client: &RpcClient) -> Result<Vec<Message>, Error> {
+        let status = rpc_client.status().await?;
+
+        if status.validator_info.power.value() == 0 {
+            return Ok(vec![]);
+        }
+
+        let latest_height = status.sync_info.latest_block_height.value();
+
+        let first_height = match self.last_block_height {
+            Some(height) => height.value() + 1,
+            None => latest_height,
+        };
+
+        // Skip ahead if we're too far behind to catch up
+        let first_height = first_height.max(latest_height.saturating_sub(MAX_BLOCKS_PER_UPDATE));
+
+        let mut output = vec![];
+
+        for height in first_height..=latest_height {
+            let response = rpc_client.block(block::Height::try_from(height)?).await?;
+            output.extend(self.apply_block(&response.block));
+        }
+
+        Ok(output)
+    }
+
+    /// Record the local validator's signature on the given block's
+    /// `last_commit`, returning any changes
+    pub fn apply_block(&mut self, block: &Block) -> Vec<Message> {
+        let mut output = vec![];
+
+        if let Some(last_block_height) = self.last_block_height {
+            if block.header.height <= last_block_height {
+                return output;
+            }
+        }
+
+        self.last_block_height = Some(block.header.height);
+
+        if let Some(commit) = &block.last_commit {
+            output.push(self.signature(commit).into());
+        }
+
+        output
+    }
+
+    /// Find the local validator's signature in the given commit
+    fn signature(&self, commit: &Commit) -> Signature {
+        let mut state = SignatureState::Absent;
+
+        for sig in &commit.signatures {
+            match sig {
+                CommitSig::BlockIdFlagCommit {
+                    validator_address, ..
+                } if validator_address == &self.validator_addr => {
+                    state = SignatureState::Signed;
+                    break;
+                }
+                CommitSig::BlockIdFlagNil {
+                    validator_address, ..
+                } if validator_address == &self.validator_addr => {
+                    state = SignatureState::Nil;
+                    break;
+                }
+                _ => (),
+            }
+        }
+
+        Signature {
+            height: commit.height,
+            validator: self.validator_addr,
+            state,
+        }
+    }
+}
+
+/// Signature of the local validator on a particular block
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Signature {
+    /// Height of the signed block
+    pub height: block::Height,
+
+    /// Address of the validator
+    pub validator: account::Id,
+
+    /// Signature state
+    pub state: SignatureState,
+}
+
+/// State of a validator's signature on a particular block
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum SignatureState {
+    /// Validator signed the block
+    #[serde(rename = "signed")]
+    Signed,
+
+    /// Validator's signature is absent from the commit
+    #[serde(rename = "absent")]
+    Absent,
+
+    /// Validator voted nil
+    #[serde(rename = "nil")]
+    Nil,
+}
+
+impl SignatureState {
+    /// Did the validator miss this block?
+    pub fn is_missed(self) -> bool {
+        match self {
+            SignatureState::Signed => false,
+            SignatureState::Absent | SignatureState::Nil => true,
+        }
+    }
+}
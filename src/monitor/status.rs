@@ -1,10 +1,11 @@
 //! Node status monitoring
 
-use super::message::Message;
+use super::{message::Message, rpc::RpcClient};
 use crate::error::Error;
 use serde::{Deserialize, Serialize};
+use tendermint::block::Block;
 pub use tendermint_rpc::endpoint::status::SyncInfo;
-use tendermint_rpc::{Client, HttpClient};
+use tendermint_rpc::Client;
 
 /// Node status monitor: monitors the `/status` RPC endpoint.
 ///
@@ -23,14 +24,14 @@ pub struct Status {
 
 impl Status {
     /// Create a new `/status` endpoint monitor
-    pub async fn new(rpc_client: &HttpClient) -> Result<Self, Error> {
+    pub async fn new(rpc_client: &RpcClient) -> Result<Self, Error> {
         Ok(Self::from(rpc_client.status().await?))
     }
 
     /// Update internal state using the given RPC client, returning any changes
     pub async fn update(
         &mut self,
-        rpc_client: &HttpClient,
+        rpc_client: &RpcClient,
         force: bool,
     ) -> Result<Vec<Message>, Error> {
         let status = rpc_client.status().await?;
@@ -54,6 +55,31 @@ impl Status {
 
         Ok(output)
     }
+
+    /// Is the node a validator (i.e. does it have voting power)?
+    pub fn is_validator(&self) -> bool {
+        self.validator.power.value() > 0
+    }
+
+    /// Update the chain status from a newly committed block (e.g. one
+    /// received via an event subscription), returning any changes
+    pub fn apply_block(&mut self, block: &Block) -> Vec<Message> {
+        let mut sync_info = self.chain.0.clone();
+        sync_info.latest_block_hash = block.header.hash();
+        sync_info.latest_app_hash = block.header.app_hash.clone();
+        sync_info.latest_block_height = block.header.height;
+        sync_info.latest_block_time = block.header.time;
+
+        let chain_status = ChainStatus(sync_info);
+        let mut output = vec![];
+
+        if chain_status != self.chain {
+            self.chain = chain_status.clone();
+            output.push(chain_status.into());
+        }
+
+        output
+    }
 }
 
 impl From<tendermint_rpc::endpoint::status::Response> for Status {
@@ -72,6 +98,23 @@ impl From<tendermint_rpc::endpoint::status::Response> for Status {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ChainStatus(SyncInfo);
 
+impl ChainStatus {
+    /// Height of the latest block
+    pub fn latest_block_height(&self) -> tendermint::block::Height {
+        self.0.latest_block_height
+    }
+
+    /// Timestamp of the latest block
+    pub fn latest_block_time(&self) -> tendermint::Time {
+        self.0.latest_block_time
+    }
+
+    /// Is the node catching up?
+    pub fn catching_up(&self) -> bool {
+        self.0.catching_up
+    }
+}
+
 impl PartialEq for ChainStatus {
     fn eq(&self, other: &Self) -> bool {
         let a = &self.0;
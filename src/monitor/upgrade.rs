@@ -0,0 +1,123 @@
+//! Upgrade readiness monitor: checks cosmovisor has the binary for any
+//! scheduled software upgrade
+
+use super::{check::Check, message::Message, rpc, rpc::RpcClient};
+use crate::prelude::*;
+use async_trait::async_trait;
+use cosmos_sdk_proto::cosmos::upgrade::v1beta1 as upgrade;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+/// Upgrade readiness monitor: queries the application's current upgrade
+/// plan and looks for the upgrade binary in the cosmovisor directory.
+#[derive(Clone, Debug)]
+pub struct Upgrade {
+    /// Path to the cosmovisor directory (e.g. `~/.gaia/cosmovisor`)
+    cosmovisor_dir: PathBuf,
+
+    /// Last reported readiness
+    last_readiness: Option<UpgradeReadiness>,
+}
+
+impl Upgrade {
+    /// Create a new upgrade readiness monitor
+    pub fn new(cosmovisor_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            cosmovisor_dir: cosmovisor_dir.into(),
+            last_readiness: None,
+        }
+    }
+
+    /// Find an executable in the `bin` directory of the given upgrade
+    fn find_binary(&self, name: &str) -> Result<Option<PathBuf>, io::Error> {
+        // Newer versions of cosmovisor lowercase upgrade names
+        for dir_name in &[name.to_owned(), name.to_lowercase()] {
+            let bin_dir = self
+                .cosmovisor_dir
+                .join("upgrades")
+                .join(dir_name)
+                .join("bin");
+
+            if let Some(binary) = find_executable(&bin_dir)? {
+                return Ok(Some(binary));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl Check for Upgrade {
+    async fn update(&mut self, rpc_client: &RpcClient, force: bool) -> Result<Vec<Message>, Error> {
+        let plan = rpc::abci_query::<_, _, upgrade::QueryCurrentPlanResponse>(
+            rpc_client,
+            "/cosmos.upgrade.v1beta1.Query/CurrentPlan",
+            upgrade::QueryCurrentPlanRequest {},
+        )
+        .await?
+        .and_then(|response| response.plan);
+
+        let readiness = match plan {
+            Some(plan) => UpgradeReadiness {
+                binary: self.find_binary(&plan.name)?,
+                plan: Some(plan.name),
+                height: Some(plan.height as u64),
+            },
+            None => UpgradeReadiness::default(),
+        };
+
+        let mut output = vec![];
+
+        if self.last_readiness.as_ref() != Some(&readiness) || force {
+            output.push(readiness.clone().into());
+            self.last_readiness = Some(readiness);
+        }
+
+        Ok(output)
+    }
+}
+
+/// Readiness of a node for the scheduled software upgrade
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct UpgradeReadiness {
+    /// Name of the scheduled upgrade (if any)
+    pub plan: Option<String>,
+
+    /// Height of the scheduled upgrade (if any)
+    pub height: Option<u64>,
+
+    /// Path to the upgrade binary (if found)
+    pub binary: Option<PathBuf>,
+}
+
+impl UpgradeReadiness {
+    /// Is the node ready for the scheduled upgrade (if any)?
+    pub fn is_ready(&self) -> bool {
+        self.plan.is_none() || self.binary.is_some()
+    }
+}
+
+/// Find an executable file in the given directory (if it exists)
+fn find_executable(dir: &Path) -> Result<Option<PathBuf>, io::Error> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    for entry in entries {
+        let path = entry?.path();
+        let metadata = fs::metadata(&path)?;
+
+        if metadata.is_file() && metadata.permissions().mode() & 0o111 != 0 {
+            return Ok(Some(path));
+        }
+    }
+
+    Ok(None)
+}
@@ -22,7 +22,7 @@ impl Network {
 
         for tm_config in &config.tendermint {
             networks.push(Network::Tendermint(Box::new(
-                self::tendermint::Network::new(tm_config.chain_id.clone()),
+                self::tendermint::Network::new(tm_config),
             )))
         }
 
@@ -50,6 +50,34 @@ impl Network {
         }
     }
 
+    /// Handle incoming staking status from the poller.
+    pub fn handle_staking_event(&mut self, event: collector::StakingEvent) {
+        match self {
+            Network::Tendermint(tm) => tm.handle_staking_event(event),
+        }
+    }
+
+    /// Handle incoming governance proposals from the poller.
+    pub fn handle_governance_event(&mut self, event: collector::GovernanceEvent) {
+        match self {
+            Network::Tendermint(tm) => tm.handle_governance_event(event),
+        }
+    }
+
+    /// Handle incoming software upgrade plan from the poller.
+    pub fn handle_upgrade_event(&mut self, event: collector::UpgradeEvent) {
+        match self {
+            Network::Tendermint(tm) => tm.handle_upgrade_event(event),
+        }
+    }
+
+    /// Handle incoming reference height from the poller.
+    pub fn handle_reference_event(&mut self, event: collector::ReferenceEvent) {
+        match self {
+            Network::Tendermint(tm) => tm.handle_reference_event(event),
+        }
+    }
+
     /// Get pager events
     pub fn get_pager_events(&mut self) -> Option<String> {
         match self {
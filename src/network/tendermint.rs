@@ -1,14 +1,54 @@
 //! Tendermint network types
 
+mod block_times;
+mod governance;
+mod node;
+mod reference;
+mod signing;
+mod staking;
+mod sync;
+mod upgrade;
+
+pub use self::{
+    block_times::{BlockIntervalStats, BlockTimes},
+    governance::{Proposal, VoteOption},
+    node::Node,
+    reference::{HeightLag, ReferenceHeight},
+    signing::SigningWindow,
+    staking::{BondStatus, ValidatorStatus},
+    sync::SyncProgress,
+    upgrade::UpgradePlan,
+};
+
 use super::Id;
 use crate::{
-    collector::PollEvent,
+    collector::{GovernanceEvent, PollEvent, ReferenceEvent, StakingEvent, UpgradeEvent},
+    config,
     message::{Envelope, Message},
-    monitor::{net_info::Peer, status::ChainStatus},
+    monitor::{
+        command::{CommandState, CommandStatus},
+        consensus::ConsensusState,
+        data::DataStatus,
+        keys::KeyAudit,
+        logs::{LogEvent, Severity},
+        mempool::MempoolStatus,
+        metrics::Metric,
+        net_info::{AnomalousPeer, ConnectionStatus, Peer},
+        priv_validator::PrivValidatorState,
+        scheduler::CheckStatus,
+        settings::NodeSettings,
+        signing::Signature,
+        status::ChainStatus,
+        upgrade::UpgradeReadiness,
+    },
     prelude::*,
 };
+use chrono::{DateTime, Utc};
 use serde::Serialize;
-use std::time::{Duration, SystemTime};
+use std::{
+    collections::{BTreeSet, VecDeque},
+    time::{Duration, SystemTime},
+};
 
 /// Tendermint network
 #[derive(Debug, Clone)]
@@ -19,33 +59,77 @@ pub struct Network {
     /// Nodes in this network
     nodes: Map<tendermint::node::Id, Node>,
 
-    /// Network peers
-    peers: Vec<Peer>,
-
     /// Chain status (if known)
     chain: Option<ChainStatus>,
 
     /// Validators
     validators: Option<tendermint::validator::Info>,
 
-    /// Page events
-    page: Vec<String>,
+    /// Recent signatures of the validator
+    signing: SigningWindow,
+
+    /// Has the validator's missed blocks threshold already been paged?
+    missed_blocks_paged: bool,
+
+    /// Times of recent blocks
+    block_times: BlockTimes,
+
+    /// Has slow block production already been paged?
+    slow_blocks_paged: bool,
+
+    /// Staking status of the validator (if known)
+    staking: Option<ValidatorStatus>,
+
+    /// Governance proposals in their voting period
+    proposals: Vec<Proposal>,
+
+    /// Proposals the validator has already been reminded to vote on
+    reminded_proposals: BTreeSet<u64>,
+
+    /// Scheduled software upgrade (if any)
+    upgrade_plan: Option<UpgradePlan>,
+
+    /// Best height of the reference RPC endpoints (if known)
+    reference: Option<ReferenceHeight>,
+
+    /// Page events, in the order they were raised
+    page: VecDeque<String>,
 
     ///Last sent page event to Datadog then forwarded to Pagerduty
     last_paged_at: Option<SystemTime>,
+
+    /// Are validators supposed to use a remote signer?
+    remote_signer: bool,
+
+    /// Expected values of node settings
+    config_baseline: Map<String, String>,
+
+    /// Alerting thresholds
+    thresholds: config::network::tendermint::thresholds::Config,
 }
 
 impl Network {
     /// Create new Tendermint network state
-    pub fn new(id: tendermint::chain::Id) -> Self {
+    pub fn new(config: &config::network::tendermint::Config) -> Self {
         Self {
-            id,
+            id: config.chain_id.clone(),
             nodes: Map::new(),
-            peers: vec![],
             chain: None,
             validators: None,
-            page: vec![],
+            signing: SigningWindow::new(config.thresholds.missed_blocks_window),
+            missed_blocks_paged: false,
+            block_times: BlockTimes::new(config.thresholds.block_time_window),
+            slow_blocks_paged: false,
+            staking: None,
+            proposals: vec![],
+            reminded_proposals: BTreeSet::new(),
+            upgrade_plan: None,
+            reference: None,
+            page: VecDeque::new(),
             last_paged_at: None,
+            remote_signer: config.remote_signer,
+            config_baseline: config.config_baseline.clone(),
+            thresholds: config.thresholds.clone(),
         }
     }
 
@@ -69,43 +153,164 @@ impl Network {
         for msg in &envelope.msg {
             match msg {
                 Message::Node(ref node_info) => self.update_node(node_info),
-                Message::Peers(ref peer_info) => self.update_peer(peer_info),
-                Message::Chain(ref chain_info) => self.update_chain(chain_info),
-                Message::Validator(ref validator_info) => self.update_validator(validator_info),
+                Message::Peers(ref peer_info) => self.update_peer(&envelope.node, peer_info),
+                Message::AnomalousPeers(ref anomalous_peers) => {
+                    self.update_anomalous_peers(&envelope.node, anomalous_peers)
+                }
+                Message::Chain(ref chain_info) => self.update_chain(&envelope.node, chain_info),
+                Message::Validator(ref validator_info) => {
+                    self.update_validator(&envelope.node, validator_info)
+                }
+                Message::Data(ref data_status) => self.update_data(&envelope.node, data_status),
+                Message::Signing(ref signature) => self.update_signing(signature),
+                Message::Consensus(ref consensus_state) => {
+                    self.update_consensus(&envelope.node, consensus_state, envelope.ts)
+                }
+                Message::Mempool(ref mempool_status) => {
+                    self.update_mempool(&envelope.node, mempool_status)
+                }
+                Message::Check(ref check_status) => self.update_check(&envelope.node, check_status),
+                Message::Command(ref command_status) => {
+                    self.update_command(&envelope.node, command_status)
+                }
+                Message::LogEvent(ref log_event) => self.update_log(&envelope.node, log_event),
+                Message::PrivValidator(ref state) => {
+                    self.update_priv_validator(&envelope.node, state)
+                }
+                Message::Keys(ref key_audit) => self.update_keys(&envelope.node, key_audit),
+                Message::Settings(ref node_settings) => {
+                    self.update_settings(&envelope.node, node_settings)
+                }
+                Message::Metrics(ref metrics) => self.update_metrics(&envelope.node, metrics),
+                Message::Upgrade(ref readiness) => self.update_upgrade(&envelope.node, readiness),
             }
         }
+
+        if let Some(node) = self.nodes.get_mut(&envelope.node) {
+            node.last_report_at = Some(Utc::now());
+        }
     }
 
     /// Handle incoming poll event
     pub fn handle_poll_event(&mut self, poll_event: PollEvent) {
         dbg!(&poll_event);
         let missed_blocks = poll_event.missed_blocks.unwrap();
-        // todo add page_threshold to config
-        let page_threshold = 10;
-        if missed_blocks > page_threshold {
-            self.page.push(format!(
+        if missed_blocks > self.thresholds.missed_blocks_threshold {
+            self.page.push_back(format!(
                 "'{}' missed {} blocks!",
                 poll_event.network_id, missed_blocks
             ));
         }
     }
 
+    /// Handle incoming staking status of the validator
+    pub fn handle_staking_event(&mut self, event: StakingEvent) {
+        let status = event.validator;
+        let last = self.staking.take();
+
+        let was_jailed = last.as_ref().map(|last| last.jailed).unwrap_or(false);
+        let was_tombstoned = last
+            .as_ref()
+            .and_then(|last| last.tombstoned)
+            .unwrap_or(false);
+        let was_bonded = last
+            .as_ref()
+            .map(|last| last.status == BondStatus::Bonded)
+            .unwrap_or(false);
+
+        if status.tombstoned == Some(true) && !was_tombstoned {
+            self.page.push_back(format!(
+                "'{}' validator {} has been tombstoned!",
+                self.id, status.operator_address
+            ));
+        } else if status.jailed && !was_jailed {
+            self.page.push_back(format!(
+                "'{}' validator {} has been jailed!",
+                self.id, status.operator_address
+            ));
+        } else if was_bonded && status.status != BondStatus::Bonded {
+            self.page.push_back(format!(
+                "'{}' validator {} has left the active set!",
+                self.id, status.operator_address
+            ));
+        }
+
+        self.staking = Some(status);
+    }
+
+    /// Handle incoming governance proposals, reminding the validator to vote
+    /// on any which end soon
+    pub fn handle_governance_event(&mut self, event: GovernanceEvent) {
+        let reminder_window =
+            chrono::Duration::seconds(self.thresholds.gov_vote_reminder_secs as i64);
+        let now = Utc::now();
+
+        self.reminded_proposals
+            .retain(|id| event.proposals.iter().any(|proposal| proposal.id == *id));
+
+        for proposal in &event.proposals {
+            let voting_end_time = match proposal.voting_end_time {
+                Some(voting_end_time) => voting_end_time,
+                None => continue,
+            };
+
+            if proposal.vote.is_none()
+                && voting_end_time - now < reminder_window
+                && self.reminded_proposals.insert(proposal.id)
+            {
+                self.page.push_back(format!(
+                    "'{}' proposal #{} ({}) ends in {}h and the validator hasn't voted!",
+                    self.id,
+                    proposal.id,
+                    proposal.title,
+                    (voting_end_time - now).num_hours()
+                ));
+            }
+        }
+
+        self.proposals = event.proposals;
+    }
+
+    /// Handle incoming best height of the reference RPC endpoints
+    pub fn handle_reference_event(&mut self, event: ReferenceEvent) {
+        self.reference = Some(event.reference);
+
+        for node_id in self.nodes.keys().cloned().collect::<Vec<_>>() {
+            self.update_height_lag(&node_id);
+        }
+    }
+
+    /// Handle incoming software upgrade plan
+    pub fn handle_upgrade_event(&mut self, event: UpgradeEvent) {
+        if event.plan != self.upgrade_plan {
+            if let Some(plan) = &event.plan {
+                info!(
+                    "'{}' upgrade {} scheduled at height {} (estimated time: {:?})",
+                    self.id, plan.name, plan.height, plan.estimated_time
+                );
+            }
+        }
+
+        self.upgrade_plan = event.plan;
+    }
+
     /// Get page events set by `PAGE_INTERVAL`
     pub fn get_page_event(&mut self) -> Option<String> {
         const PAGE_INTERVAL: Duration = Duration::from_secs(10 * 60);
 
-        if let Some(page) = self.page.pop() {
-            if let Some(last_paged_at) = self.last_paged_at {
-                if SystemTime::now().duration_since(last_paged_at).unwrap() < PAGE_INTERVAL {
-                    return None;
-                }
-            }
+        self.check_missing_agents();
+        self.expire_log_alerts();
 
-            self.last_paged_at = Some(SystemTime::now());
-            return Some(page);
+        // Leave pages queued until the rate limit allows sending them
+        if let Some(last_paged_at) = self.last_paged_at {
+            if SystemTime::now().duration_since(last_paged_at).unwrap() < PAGE_INTERVAL {
+                return None;
+            }
         }
 
-        None
+        let page = self.page.pop_front()?;
+        self.last_paged_at = Some(SystemTime::now());
+        Some(page)
     }
 
     /// Update information about a particular node
@@ -123,40 +328,740 @@ impl Network {
         }
     }
 
-    /// Update information about peers
-    fn update_peer(&mut self, peer_info: &[Peer]) {
+    /// Update information about a node's peers
+    fn update_peer(&mut self, node_id: &tendermint::node::Id, peer_info: &[Peer]) {
         info!("peers update: {:?} ", peer_info);
-        self.peers = peer_info.to_vec();
+
+        let node = match self.nodes.get_mut(node_id) {
+            Some(node) => node,
+            None => {
+                debug!("ignoring peers for unknown node: {}", node_id);
+                return;
+            }
+        };
+
+        let throughput = |peer: &Peer| {
+            peer.stats
+                .as_ref()
+                .map(|stats| stats.send.avg_rate + stats.recv.avg_rate)
+                .unwrap_or_default()
+        };
+
+        let mut peers = peer_info.to_vec();
+        peers.sort_by_key(|peer| std::cmp::Reverse(throughput(peer)));
+
+        for peer in &peers {
+            if peer
+                .stats
+                .as_ref()
+                .map(|stats| stats.is_saturated())
+                .unwrap_or(false)
+            {
+                warn!(
+                    "'{}' node {} link to {} is saturated",
+                    self.id, node.moniker, peer.addr
+                );
+            }
+        }
+
+        let stats = peers.iter().filter_map(|peer| peer.stats.as_ref());
+        node.send_rate = stats.clone().map(|stats| stats.send.cur_rate).sum();
+        node.recv_rate = stats.map(|stats| stats.recv.cur_rate).sum();
+
+        let now = Utc::now();
+        let mut disconnected_peers = Map::new();
+
+        for peer in &peers {
+            let peer_id = match peer.id() {
+                Some(id) if peer.persistent || peer.private => id,
+                _ => continue,
+            };
+
+            if peer.connection != ConnectionStatus::None {
+                node.alert(&format!("peer_disconnected:{}", peer_id), false);
+                continue;
+            }
+
+            let since = node
+                .disconnected_peers
+                .get(&peer_id)
+                .copied()
+                .unwrap_or(now);
+
+            disconnected_peers.insert(peer_id, since);
+
+            let grace_secs = if peer.private {
+                self.thresholds.private_peer_disconnect_secs
+            } else {
+                self.thresholds.peer_disconnect_secs
+            };
+
+            let disconnected_secs = now.signed_duration_since(since).num_seconds();
+
+            if node.alert(
+                &format!("peer_disconnected:{}", peer_id),
+                disconnected_secs > grace_secs as i64,
+            ) {
+                self.page.push_back(format!(
+                    "'{}' node {} has been disconnected from {} peer {} for {}s!",
+                    self.id,
+                    node.moniker,
+                    if peer.private {
+                        "private"
+                    } else {
+                        "persistent"
+                    },
+                    peer.addr,
+                    disconnected_secs
+                ));
+            }
+        }
+
+        // Clear alerts for peers which are no longer configured
+        for peer_id in node.disconnected_peers.keys().cloned().collect::<Vec<_>>() {
+            if !disconnected_peers.contains_key(&peer_id) {
+                node.alert(&format!("peer_disconnected:{}", peer_id), false);
+            }
+        }
+
+        node.disconnected_peers = disconnected_peers;
+        node.peers = peers;
+    }
+
+    /// Update the list of peers a node couldn't process
+    fn update_anomalous_peers(
+        &mut self,
+        node_id: &tendermint::node::Id,
+        anomalous_peers: &[AnomalousPeer],
+    ) {
+        let node = match self.nodes.get_mut(node_id) {
+            Some(node) => node,
+            None => {
+                debug!("ignoring anomalous peers for unknown node: {}", node_id);
+                return;
+            }
+        };
+
+        for anomalous_peer in anomalous_peers {
+            if !node.anomalous_peers.contains(anomalous_peer) {
+                warn!(
+                    "'{}' node {} has anomalous peer: {:?}",
+                    self.id, node.moniker, anomalous_peer
+                );
+            }
+        }
+
+        node.anomalous_peers = anomalous_peers.to_vec();
     }
 
     /// Update information about chain status
-    fn update_chain(&mut self, chain_info: &ChainStatus) {
+    fn update_chain(&mut self, node_id: &tendermint::node::Id, chain_info: &ChainStatus) {
         info!("chain status update: {:?}", chain_info);
+
+        let target_height = self.best_height();
+        let chain_blocks_per_sec = self
+            .block_times
+            .stats()
+            .filter(|stats| stats.p50 > 0.0)
+            .map(|stats| 1.0 / stats.p50);
+
+        if let Some(node) = self.nodes.get_mut(node_id) {
+            node.sync_progress = if chain_info.catching_up() {
+                Some(SyncProgress::update(
+                    node.sync_progress.as_ref(),
+                    chain_info.latest_block_height().value(),
+                    target_height,
+                    chain_blocks_per_sec,
+                    Utc::now(),
+                ))
+            } else {
+                None
+            };
+
+            node.chain = Some(chain_info.clone());
+        }
+
+        self.update_height_lag(node_id);
+
+        if !chain_info.catching_up() {
+            self.block_times.record(
+                chain_info.latest_block_height(),
+                chain_info.latest_block_time(),
+            );
+            self.check_block_times();
+        }
+
         self.chain = Some(chain_info.clone());
     }
 
+    /// Best known height of the network: the highest height reported by the
+    /// reference RPC endpoints or by any node which isn't catching up
+    fn best_height(&self) -> u64 {
+        self.nodes
+            .values()
+            .filter_map(|node| node.chain.as_ref())
+            .filter(|chain| !chain.catching_up())
+            .map(|chain| chain.latest_block_height().value())
+            .chain(self.reference.iter().map(|reference| reference.height))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Update how far the given node is behind the reference height, paging
+    /// if it exceeds the configured thresholds
+    fn update_height_lag(&mut self, node_id: &tendermint::node::Id) {
+        let reference = match &self.reference {
+            Some(reference) => reference,
+            None => return,
+        };
+
+        let node = match self.nodes.get_mut(node_id) {
+            Some(node) => node,
+            None => return,
+        };
+
+        let chain = match &node.chain {
+            Some(chain) => chain,
+            None => return,
+        };
+
+        let lag = HeightLag::new(
+            reference,
+            chain.latest_block_height().value(),
+            chain.latest_block_time(),
+        );
+
+        let lagging = lag.blocks > self.thresholds.height_lag_blocks
+            || lag.secs > self.thresholds.height_lag_secs;
+
+        if node.alert("height_lag", lagging) {
+            self.page.push_back(format!(
+                "'{}' node {} is {} blocks ({}s) behind reference height {} ({})!",
+                self.id, node.moniker, lag.blocks, lag.secs, reference.height, reference.endpoint
+            ));
+        }
+
+        node.height_lag = Some(lag);
+    }
+
+    /// Page when block production has slowed beyond the configured multiple
+    /// of the median block interval
+    fn check_block_times(&mut self) {
+        let stats = match self.block_times.stats() {
+            Some(stats) if stats.samples >= 2 * block_times::RECENT_INTERVALS => stats,
+            _ => return,
+        };
+
+        if stats.recent_mean > stats.p50 * self.thresholds.block_time_slowdown_ratio {
+            if !self.slow_blocks_paged {
+                self.page.push_back(format!(
+                    "'{}' block production has slowed to {:.1}s per block (median {:.1}s)!",
+                    self.id, stats.recent_mean, stats.p50
+                ));
+                self.slow_blocks_paged = true;
+            }
+        } else {
+            self.slow_blocks_paged = false;
+        }
+    }
+
     /// Update information about validators
-    fn update_validator(&mut self, validator_info: &tendermint::validator::Info) {
+    fn update_validator(
+        &mut self,
+        node_id: &tendermint::node::Id,
+        validator_info: &tendermint::validator::Info,
+    ) {
         info!("validator update: {:?}", validator_info);
         self.validators = Some(validator_info.clone());
+
+        if let Some(node) = self.nodes.get_mut(node_id) {
+            node.validator = Some(validator_info.clone());
+        }
     }
-}
 
-/// Nodes in Tendermint network
-#[derive(Clone, Debug, Serialize)]
-pub struct Node {
-    /// Node ID
-    pub id: tendermint::node::Id,
+    /// Update information about a node's database directory
+    fn update_data(&mut self, node_id: &tendermint::node::Id, data_status: &DataStatus) {
+        let horizon = Duration::from_secs(self.thresholds.disk_full_horizon_secs);
 
-    /// Node moniker
-    pub moniker: tendermint::Moniker,
-}
+        let node = match self.nodes.get_mut(node_id) {
+            Some(node) => node,
+            None => {
+                debug!("ignoring data status for unknown node: {}", node_id);
+                return;
+            }
+        };
+
+        let time_until_full = data_status
+            .time_until_full()
+            .filter(|&time_until_full| time_until_full < horizon);
+
+        if node.alert("disk_full", time_until_full.is_some()) {
+            self.page.push_back(format!(
+                "'{}' node {} will run out of disk space in ~{}h!",
+                self.id,
+                node.moniker,
+                time_until_full.unwrap_or_default().as_secs() / 3600
+            ));
+        }
+
+        node.data = Some(data_status.clone());
+    }
+
+    /// Update information about a node's consensus state
+    fn update_consensus(
+        &mut self,
+        node_id: &tendermint::node::Id,
+        consensus_state: &ConsensusState,
+        reported_at: DateTime<Utc>,
+    ) {
+        let max_round = self.thresholds.consensus_max_round;
+        let stall_secs = self.thresholds.consensus_stall_secs as i64;
+
+        let node = match self.nodes.get_mut(node_id) {
+            Some(node) => node,
+            None => {
+                debug!("ignoring consensus state for unknown node: {}", node_id);
+                return;
+            }
+        };
+
+        let votes = match consensus_state.current_round_votes() {
+            Some(votes) => format!(
+                "{}% of prevotes and {}% of precommits missing",
+                votes.prevotes.missing_percent(),
+                votes.precommits.missing_percent()
+            ),
+            None => "no votes received".to_owned(),
+        };
+
+        if node.alert("consensus_round", consensus_state.round > max_round) {
+            self.page.push_back(format!(
+                "'{}' node {} reached round {} at height {} ({})!",
+                self.id, node.moniker, consensus_state.round, consensus_state.height, votes
+            ));
+        }
+
+        // Measured up to when the agent reported the state rather than the
+        // current time, so an agent which stops reporting isn't mistaken for
+        // a stall (agents re-report their full state periodically)
+        let stalled_secs = reported_at
+            .signed_duration_since(consensus_state.height_since)
+            .num_seconds();
+
+        if node.alert("consensus_stall", stalled_secs > stall_secs) {
+            self.page.push_back(format!(
+                "'{}' node {} stuck at height {} for {}s (round {}, {}, {} of {} peers ahead)!",
+                self.id,
+                node.moniker,
+                consensus_state.height,
+                stalled_secs,
+                consensus_state.round,
+                votes,
+                consensus_state.peers_ahead(),
+                consensus_state.peers.len()
+            ));
+        }
 
-impl<'a> From<&'a tendermint::node::Info> for Node {
-    fn from(node_info: &'a tendermint::node::Info) -> Node {
-        Node {
-            id: node_info.id,
-            moniker: node_info.moniker.clone(),
+        node.consensus = Some(consensus_state.clone());
+    }
+
+    /// Check whether any node's agent has stopped reporting.
+    ///
+    /// A missing agent sends no messages, so this is evaluated against the
+    /// current time rather than when messages arrive.
+    fn check_missing_agents(&mut self) {
+        let missing_secs = self.thresholds.agent_missing_secs as i64;
+        let now = Utc::now();
+
+        for node in self.nodes.values_mut() {
+            let silent_secs = match node.last_report_at {
+                Some(last_report_at) => now.signed_duration_since(last_report_at).num_seconds(),
+                None => continue,
+            };
+
+            if node.alert("agent_missing", silent_secs > missing_secs) {
+                self.page.push_back(format!(
+                    "'{}' node {} agent hasn't reported for {}s!",
+                    self.id, node.moniker, silent_secs
+                ));
+            }
+        }
+    }
+
+    /// Update information about a node's mempool
+    fn update_mempool(&mut self, node_id: &tendermint::node::Id, mempool_status: &MempoolStatus) {
+        let fill_ratio = self.thresholds.mempool_fill_ratio;
+        let fill_secs = self.thresholds.mempool_fill_secs as i64;
+
+        let node = match self.nodes.get_mut(node_id) {
+            Some(node) => node,
+            None => {
+                debug!("ignoring mempool status for unknown node: {}", node_id);
+                return;
+            }
+        };
+
+        let now = Utc::now();
+
+        node.mempool_full_since = if mempool_status.fill_ratio() > fill_ratio {
+            Some(node.mempool_full_since.unwrap_or(now))
+        } else {
+            None
+        };
+
+        let full_secs = node
+            .mempool_full_since
+            .map(|since| now.signed_duration_since(since).num_seconds())
+            .unwrap_or_default();
+
+        if node.alert("mempool_full", full_secs > fill_secs) {
+            self.page.push_back(format!(
+                "'{}' node {} mempool has been {}% full for {}s ({} txs, {} bytes)!",
+                self.id,
+                node.moniker,
+                (mempool_status.fill_ratio() * 100.0) as u64,
+                full_secs,
+                mempool_status.n_txs,
+                mempool_status.total_bytes
+            ));
+        }
+
+        node.mempool = Some(mempool_status.clone());
+    }
+
+    /// Update the status of a check performed by a node's agent
+    fn update_check(&mut self, node_id: &tendermint::node::Id, check_status: &CheckStatus) {
+        let node = match self.nodes.get_mut(node_id) {
+            Some(node) => node,
+            None => {
+                debug!("ignoring check status for unknown node: {}", node_id);
+                return;
+            }
+        };
+
+        if check_status.is_failing() {
+            warn!(
+                "'{}' node {} {} check failing ({} in a row): {}",
+                self.id,
+                node.moniker,
+                check_status.check,
+                check_status.consecutive_failures,
+                check_status.error.as_deref().unwrap_or_default()
+            );
+
+            node.failing_checks
+                .insert(check_status.check.clone(), check_status.clone());
+        } else {
+            node.failing_checks.remove(&check_status.check);
+        }
+    }
+
+    /// Update the result of an external command run by a node's agent
+    fn update_command(&mut self, node_id: &tendermint::node::Id, command_status: &CommandStatus) {
+        let node = match self.nodes.get_mut(node_id) {
+            Some(node) => node,
+            None => {
+                debug!("ignoring command status for unknown node: {}", node_id);
+                return;
+            }
+        };
+
+        if command_status.state == CommandState::Warning {
+            warn!(
+                "'{}' node {} command {} is WARNING: {}",
+                self.id, node.moniker, command_status.name, command_status.output
+            );
+        }
+
+        let alert = format!("command:{}", command_status.name);
+
+        if node.alert(&alert, command_status.state == CommandState::Critical) {
+            self.page.push_back(format!(
+                "'{}' node {} command {} is CRITICAL: {}!",
+                self.id, node.moniker, command_status.name, command_status.output
+            ));
+        }
+
+        node.commands
+            .insert(command_status.name.clone(), command_status.clone());
+    }
+
+    /// Record a log event from a node
+    fn update_log(&mut self, node_id: &tendermint::node::Id, log_event: &LogEvent) {
+        /// Number of log events kept for each node
+        const MAX_LOG_EVENTS: usize = 20;
+
+        let node = match self.nodes.get_mut(node_id) {
+            Some(node) => node,
+            None => {
+                debug!("ignoring log event for unknown node: {}", node_id);
+                return;
+            }
+        };
+
+        match log_event.severity {
+            Severity::Info => info!(
+                "'{}' node {} logged {} ({}x): {}",
+                self.id, node.moniker, log_event.pattern, log_event.count, log_event.line
+            ),
+            Severity::Warning => warn!(
+                "'{}' node {} logged {} ({}x): {}",
+                self.id, node.moniker, log_event.pattern, log_event.count, log_event.line
+            ),
+            Severity::Critical => {
+                let alert = format!("log:{}", log_event.pattern);
+
+                if node.alert(&alert, true) {
+                    self.page.push_back(format!(
+                        "'{}' node {} logged {}: {}!",
+                        self.id, node.moniker, log_event.pattern, log_event.line
+                    ));
+                }
+
+                node.critical_logs_at
+                    .insert(log_event.pattern.clone(), Utc::now());
+            }
+        }
+
+        node.log_events.push(log_event.clone());
+
+        if node.log_events.len() > MAX_LOG_EVENTS {
+            let excess = node.log_events.len() - MAX_LOG_EVENTS;
+            node.log_events.drain(..excess);
+        }
+    }
+
+    /// Clear alerts for critical log patterns which haven't been matched for
+    /// a while, so they're paged again if they recur
+    fn expire_log_alerts(&mut self) {
+        /// Time after which a critical log pattern can be paged again
+        const LOG_ALERT_QUIET_PERIOD: i64 = 10 * 60;
+
+        let now = Utc::now();
+
+        for node in self.nodes.values_mut() {
+            let expired = node
+                .critical_logs_at
+                .iter()
+                .filter(|(_, at)| {
+                    now.signed_duration_since(**at).num_seconds() > LOG_ALERT_QUIET_PERIOD
+                })
+                .map(|(pattern, _)| pattern.clone())
+                .collect::<Vec<_>>();
+
+            for pattern in expired {
+                node.critical_logs_at.remove(&pattern);
+                node.alert(&format!("log:{}", pattern), false);
+            }
+        }
+    }
+
+    /// Update the signing state of a node's validator key
+    fn update_priv_validator(
+        &mut self,
+        node_id: &tendermint::node::Id,
+        state: &PrivValidatorState,
+    ) {
+        let lag_blocks = self.thresholds.signing_lag_blocks;
+        let window = chrono::Duration::seconds(self.thresholds.double_sign_window_secs as i64);
+        let now = Utc::now();
+
+        let chain_height = self
+            .chain
+            .as_ref()
+            .map(|chain| chain.latest_block_height().value());
+
+        // Other nodes which have recently signed with the same key
+        let other_signers = self
+            .nodes
+            .values()
+            .filter(|node| node.id != *node_id)
+            .filter(|node| {
+                node.priv_validator
+                    .as_ref()
+                    .map(|other| other.validator == state.validator)
+                    .unwrap_or(false)
+            })
+            .filter(|node| {
+                node.priv_validator_advanced_at
+                    .map(|advanced_at| now.signed_duration_since(advanced_at) < window)
+                    .unwrap_or(false)
+            })
+            .map(|node| node.moniker.to_string())
+            .collect::<Vec<_>>();
+
+        let node = match self.nodes.get_mut(node_id) {
+            Some(node) => node,
+            None => {
+                debug!("ignoring signing state for unknown node: {}", node_id);
+                return;
+            }
+        };
+
+        if let Some(last_state) = &node.priv_validator {
+            if state.is_ahead_of(last_state) {
+                node.priv_validator_advanced_at = Some(now);
+            }
+        }
+
+        let recently_advanced = node
+            .priv_validator_advanced_at
+            .map(|advanced_at| now.signed_duration_since(advanced_at) < window)
+            .unwrap_or(false);
+
+        if node.alert(
+            "double_sign_risk",
+            recently_advanced && !other_signers.is_empty(),
+        ) {
+            self.page.push_back(format!(
+                "'{}' validator {} is signing on node {} and {}: double-sign risk!",
+                self.id,
+                state.validator,
+                node.moniker,
+                other_signers.join(", ")
+            ));
+        }
+
+        let lagging = chain_height
+            .map(|chain_height| chain_height > state.height + lag_blocks)
+            .unwrap_or(false);
+
+        // Only the node the validator is actually signing on has voting
+        // power in its own status (sentries and standbys report none)
+        let is_active_validator = node
+            .validator
+            .as_ref()
+            .map(|validator| validator.address == state.validator && validator.power.value() > 0)
+            .unwrap_or(false);
+
+        if node.alert("signing_stalled", is_active_validator && lagging) {
+            self.page.push_back(format!(
+                "'{}' node {} validator last signed at height {} (chain at {})!",
+                self.id,
+                node.moniker,
+                state.height,
+                chain_height.unwrap_or_default()
+            ));
+        }
+
+        node.priv_validator = Some(state.clone());
+    }
+
+    /// Update the key material audit of a node
+    fn update_keys(&mut self, node_id: &tendermint::node::Id, key_audit: &KeyAudit) {
+        let node = match self.nodes.get_mut(node_id) {
+            Some(node) => node,
+            None => {
+                debug!("ignoring key audit for unknown node: {}", node_id);
+                return;
+            }
+        };
+
+        if node.alert(
+            "plaintext_consensus_key",
+            self.remote_signer && key_audit.consensus_key_on_disk,
+        ) {
+            self.page.push_back(format!(
+                "'{}' node {} is validating with a plaintext consensus key instead of a remote signer!",
+                self.id, node.moniker
+            ));
+        }
+
+        let world_readable = key_audit.world_readable();
+
+        if node.alert("world_readable_keys", !world_readable.is_empty()) {
+            self.page.push_back(format!(
+                "'{}' node {} has world-readable key files: {}!",
+                self.id,
+                node.moniker,
+                world_readable
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        node.keys = Some(key_audit.clone());
+    }
+
+    /// Update the configuration of a node, checking it against the baseline
+    fn update_settings(&mut self, node_id: &tendermint::node::Id, node_settings: &NodeSettings) {
+        let node = match self.nodes.get_mut(node_id) {
+            Some(node) => node,
+            None => {
+                debug!("ignoring settings for unknown node: {}", node_id);
+                return;
+            }
+        };
+
+        for (key, expected) in &self.config_baseline {
+            let actual = node_settings
+                .settings
+                .get(key)
+                .map(String::as_str)
+                .unwrap_or("<unset>");
+
+            if node.alert(&format!("config_drift:{}", key), actual != expected) {
+                self.page.push_back(format!(
+                    "'{}' node {} has {} = {} (expected {})!",
+                    self.id, node.moniker, key, actual, expected
+                ));
+            }
+        }
+
+        // Nodes whose fingerprint differs from the rest of the fleet are
+        // reported in the state API, but only baseline drift pages
+        node.settings = Some(node_settings.clone());
+    }
+
+    /// Update the Prometheus metrics of a node
+    fn update_metrics(&mut self, node_id: &tendermint::node::Id, metrics: &[Metric]) {
+        match self.nodes.get_mut(node_id) {
+            Some(node) => node.metrics = metrics.to_vec(),
+            None => debug!("ignoring metrics for unknown node: {}", node_id),
+        }
+    }
+
+    /// Update a node's readiness for the scheduled software upgrade
+    fn update_upgrade(&mut self, node_id: &tendermint::node::Id, readiness: &UpgradeReadiness) {
+        let node = match self.nodes.get_mut(node_id) {
+            Some(node) => node,
+            None => {
+                debug!("ignoring upgrade readiness for unknown node: {}", node_id);
+                return;
+            }
+        };
+
+        if node.alert("upgrade_binary_missing", !readiness.is_ready()) {
+            self.page.push_back(format!(
+                "'{}' node {} is missing the binary for upgrade {} at height {}!",
+                self.id,
+                node.moniker,
+                readiness.plan.as_deref().unwrap_or_default(),
+                readiness.height.unwrap_or_default()
+            ));
+        }
+
+        node.upgrade = Some(readiness.clone());
+    }
+
+    /// Update the rolling window of validator signatures
+    fn update_signing(&mut self, signature: &Signature) {
+        self.signing.record(signature.height, signature.state);
+
+        let missed_blocks = self.signing.missed_blocks();
+
+        if missed_blocks > self.thresholds.missed_blocks_threshold {
+            if !self.missed_blocks_paged {
+                self.page.push_back(format!(
+                    "'{}' missed {} of the last {} blocks!",
+                    self.id, missed_blocks, self.thresholds.missed_blocks_window
+                ));
+                self.missed_blocks_paged = true;
+            }
+        } else {
+            self.missed_blocks_paged = false;
         }
     }
 }
@@ -165,18 +1070,128 @@ impl<'a> From<&'a tendermint::node::Info> for Node {
 #[derive(Debug, Serialize)]
 pub struct State {
     nodes: Vec<Node>,
-    peers: Vec<Peer>,
     chain: Option<ChainStatus>,
     validators: Option<tendermint::validator::Info>,
+    signing: SigningWindow,
+    staking: Option<ValidatorStatus>,
+    proposals: Vec<Proposal>,
+    upgrade_plan: Option<UpgradePlan>,
+    block_interval: Option<BlockIntervalStats>,
+    reference: Option<ReferenceHeight>,
+    missed_blocks: usize,
+    config_drift: Map<String, Map<String, String>>,
+    config_outliers: Vec<String>,
 }
 
 impl State {
     fn new(network: &Network) -> Self {
         Self {
             nodes: network.nodes.values().cloned().collect(),
-            peers: network.peers.clone(),
             chain: network.chain.clone(),
             validators: network.validators.clone(),
+            signing: network.signing.clone(),
+            staking: network.staking.clone(),
+            proposals: network.proposals.clone(),
+            upgrade_plan: network.upgrade_plan.clone(),
+            block_interval: network.block_times.stats(),
+            reference: network.reference.clone(),
+            missed_blocks: network.signing.missed_blocks(),
+            config_drift: config_drift(network.nodes.values()),
+            config_outliers: config_outliers(network.nodes.values()),
         }
     }
 }
+
+/// Find the config fingerprint shared by a majority of the nodes which have
+/// reported their settings (if there is one)
+fn majority_fingerprint<'a>(nodes: impl Iterator<Item = &'a Node>) -> Option<String> {
+    let mut counts = Map::new();
+    let mut total = 0;
+
+    for settings in nodes.filter_map(|node| node.settings.as_ref()) {
+        *counts.entry(&settings.fingerprint).or_insert(0) += 1;
+        total += 1;
+    }
+
+    counts
+        .into_iter()
+        .find(|(_, count)| *count * 2 > total)
+        .map(|(fingerprint, _)| fingerprint.clone())
+}
+
+/// Find the monikers of nodes whose config fingerprint differs from the
+/// majority of the fleet
+fn config_outliers<'a>(nodes: impl Iterator<Item = &'a Node> + Clone) -> Vec<String> {
+    let majority = match majority_fingerprint(nodes.clone()) {
+        Some(majority) => majority,
+        None => return vec![],
+    };
+
+    nodes
+        .filter(|node| {
+            node.settings
+                .as_ref()
+                .map(|settings| settings.fingerprint != majority)
+                .unwrap_or(false)
+        })
+        .map(|node| node.moniker.to_string())
+        .collect()
+}
+
+/// Find key settings whose values differ between nodes, returning the
+/// value of each such setting keyed by node moniker
+fn config_drift<'a>(nodes: impl Iterator<Item = &'a Node>) -> Map<String, Map<String, String>> {
+    let nodes = nodes
+        .filter_map(|node| node.settings.as_ref().map(|settings| (node, settings)))
+        .collect::<Vec<_>>();
+
+    let keys = nodes
+        .iter()
+        .flat_map(|(_, node_settings)| node_settings.settings.keys())
+        .collect::<BTreeSet<_>>();
+
+    let mut drift = Map::new();
+
+    for key in keys {
+        let by_node = nodes
+            .iter()
+            .map(|(node, node_settings)| {
+                let value = node_settings
+                    .settings
+                    .get(key)
+                    .cloned()
+                    .unwrap_or_else(|| "<unset>".to_owned());
+
+                (node.moniker.to_string(), value)
+            })
+            .collect::<Map<_, _>>();
+
+        if by_node.values().collect::<BTreeSet<_>>().len() > 1 {
+            drift.insert(key.clone(), by_node);
+        }
+    }
+
+    drift
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Network;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn queues_pages_until_rate_limit_allows() {
+        let config = toml::from_str("chain_id = \"test-1\"").unwrap();
+        let mut network = Network::new(&config);
+
+        network.page.push_back("first".to_owned());
+        network.page.push_back("second".to_owned());
+
+        assert_eq!(network.get_page_event().as_deref(), Some("first"));
+        assert_eq!(network.get_page_event(), None);
+
+        network.last_paged_at = Some(SystemTime::now() - Duration::from_secs(11 * 60));
+        assert_eq!(network.get_page_event().as_deref(), Some("second"));
+        assert!(network.page.is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+//! Rolling window of block times
+
+use crate::prelude::*;
+use serde::Serialize;
+use tendermint::{block, Time};
+
+/// Number of most recent block intervals compared against the baseline
+pub const RECENT_INTERVALS: usize = 10;
+
+/// Rolling window of the times of recent blocks, as reported by agents
+#[derive(Clone, Debug)]
+pub struct BlockTimes {
+    /// Maximum number of blocks in the window
+    size: usize,
+
+    /// Time of each block in the window
+    times: Map<block::Height, Time>,
+}
+
+impl BlockTimes {
+    /// Create a new block time window of the given size
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            times: Map::new(),
+        }
+    }
+
+    /// Record the time of a block, evicting the oldest ones which no longer
+    /// fit in the window
+    pub fn record(&mut self, height: block::Height, time: Time) {
+        self.times.insert(height, time);
+
+        while self.times.len() > self.size {
+            let oldest = *self.times.keys().next().unwrap();
+            self.times.remove(&oldest);
+        }
+    }
+
+    /// Intervals between blocks in seconds, oldest first.
+    ///
+    /// When blocks in between weren't reported, the interval between the
+    /// reported ones is averaged over the blocks produced.
+    pub fn intervals(&self) -> Vec<f64> {
+        let mut intervals = vec![];
+
+        for ((height_a, time_a), (height_b, time_b)) in
+            self.times.iter().zip(self.times.iter().skip(1))
+        {
+            let blocks = height_b.value() - height_a.value();
+
+            if let Ok(elapsed) = time_b.duration_since(*time_a) {
+                intervals.push(elapsed.as_secs_f64() / blocks as f64);
+            }
+        }
+
+        intervals
+    }
+
+    /// Compute statistics about the intervals in the window (if there are
+    /// any)
+    pub fn stats(&self) -> Option<BlockIntervalStats> {
+        let intervals = self.intervals();
+
+        if intervals.is_empty() {
+            return None;
+        }
+
+        let recent = &intervals[intervals.len().saturating_sub(RECENT_INTERVALS)..];
+        let recent_mean = recent.iter().sum::<f64>() / recent.len() as f64;
+
+        let mut sorted = intervals.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        Some(BlockIntervalStats {
+            samples: sorted.len(),
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p50: percentile(&sorted, 0.5),
+            p95: percentile(&sorted, 0.95),
+            max: sorted[sorted.len() - 1],
+            recent_mean,
+        })
+    }
+}
+
+/// Statistics about the intervals between recent blocks, in seconds
+#[derive(Clone, Debug, Serialize)]
+pub struct BlockIntervalStats {
+    /// Number of intervals the statistics are computed from
+    pub samples: usize,
+
+    /// Mean interval
+    pub mean: f64,
+
+    /// Median interval
+    pub p50: f64,
+
+    /// 95th percentile interval
+    pub p95: f64,
+
+    /// Longest interval
+    pub max: f64,
+
+    /// Mean of the most recent intervals
+    pub recent_mean: f64,
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.saturating_sub(1).min(sorted.len() - 1)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_interval_stats() {
+        let mut window = BlockTimes::new(5);
+
+        // Blocks at 0, 5, 10, 20, 26 and 46 seconds (the first is evicted),
+        // with height 4 not reported
+        for (height, secs) in &[(1u32, 0), (2, 5), (3, 10), (5, 20), (6, 26), (7, 46)] {
+            window.record(
+                block::Height::from(*height),
+                Time::from_unix_timestamp(*secs, 0).unwrap(),
+            );
+        }
+
+        assert_eq!(window.intervals(), vec![5.0, 5.0, 6.0, 20.0]);
+
+        let stats = window.stats().unwrap();
+        assert_eq!(stats.samples, 4);
+        assert_eq!(stats.mean, 9.0);
+        assert_eq!(stats.p50, 5.0);
+        assert_eq!(stats.p95, 20.0);
+        assert_eq!(stats.max, 20.0);
+        assert_eq!(stats.recent_mean, 9.0);
+    }
+}
@@ -0,0 +1,44 @@
+//! Governance proposals of a network
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Governance proposal in its voting period
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Proposal {
+    /// Proposal ID
+    pub id: u64,
+
+    /// Proposal title
+    pub title: String,
+
+    /// End of the voting period (if known)
+    pub voting_end_time: Option<DateTime<Utc>>,
+
+    /// How the validator voted (if it has)
+    pub vote: Option<VoteOption>,
+}
+
+/// Vote on a governance proposal
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum VoteOption {
+    /// Option isn't specified
+    #[serde(rename = "unspecified")]
+    Unspecified,
+
+    /// Yes
+    #[serde(rename = "yes")]
+    Yes,
+
+    /// Abstain
+    #[serde(rename = "abstain")]
+    Abstain,
+
+    /// No
+    #[serde(rename = "no")]
+    No,
+
+    /// No with veto
+    #[serde(rename = "no_with_veto")]
+    NoWithVeto,
+}
@@ -0,0 +1,164 @@
+//! Nodes in a Tendermint network
+
+use super::{HeightLag, SyncProgress};
+use crate::{
+    monitor::{
+        command::CommandStatus,
+        consensus::ConsensusState,
+        data::DataStatus,
+        keys::KeyAudit,
+        logs::LogEvent,
+        mempool::MempoolStatus,
+        metrics::Metric,
+        net_info::{AnomalousPeer, Peer},
+        priv_validator::PrivValidatorState,
+        scheduler::CheckStatus,
+        settings::NodeSettings,
+        status::ChainStatus,
+        upgrade::UpgradeReadiness,
+    },
+    prelude::*,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Nodes in Tendermint network
+#[derive(Clone, Debug, Serialize)]
+pub struct Node {
+    /// Node ID
+    pub id: tendermint::node::Id,
+
+    /// Node moniker
+    pub moniker: tendermint::Moniker,
+
+    /// Peers of this node, highest throughput first
+    pub peers: Vec<Peer>,
+
+    /// Peers this node couldn't process (e.g. due to invalid listen addresses)
+    pub anomalous_peers: Vec<AnomalousPeer>,
+
+    /// Current rate at which this node is sending data to its peers in bytes
+    /// per second
+    pub send_rate: u64,
+
+    /// Current rate at which this node is receiving data from its peers in
+    /// bytes per second
+    pub recv_rate: u64,
+
+    /// Persistent and private peers which are currently disconnected, and
+    /// since when
+    pub disconnected_peers: Map<tendermint::node::Id, DateTime<Utc>>,
+
+    /// Chain synchronization status reported by this node (if known)
+    pub chain: Option<ChainStatus>,
+
+    /// How far this node is behind the reference RPC endpoints (if known)
+    pub height_lag: Option<HeightLag>,
+
+    /// Catch-up progress (if the node is syncing)
+    pub sync_progress: Option<SyncProgress>,
+
+    /// Validator status as reported by the node (if known)
+    pub validator: Option<tendermint::validator::Info>,
+
+    /// Database directory status (if known)
+    pub data: Option<DataStatus>,
+
+    /// Consensus state (if known)
+    pub consensus: Option<ConsensusState>,
+
+    /// Mempool status (if known)
+    pub mempool: Option<MempoolStatus>,
+
+    /// Last signing state of the node's validator key (if known)
+    pub priv_validator: Option<PrivValidatorState>,
+
+    /// Key material audit (if known)
+    pub keys: Option<KeyAudit>,
+
+    /// Configuration fingerprint and key settings (if known)
+    pub settings: Option<NodeSettings>,
+
+    /// Readiness for the scheduled software upgrade (if the node is run by
+    /// cosmovisor)
+    pub upgrade: Option<UpgradeReadiness>,
+
+    /// Latest allowlisted Prometheus metrics
+    pub metrics: Vec<Metric>,
+
+    /// Latest results of external commands, keyed by command name
+    pub commands: Map<String, CommandStatus>,
+
+    /// Most recent log events, oldest first
+    pub log_events: Vec<LogEvent>,
+
+    /// Checks which are currently failing on this node's agent
+    pub failing_checks: Map<String, CheckStatus>,
+
+    /// Time the node's agent last reported (if it has)
+    pub last_report_at: Option<DateTime<Utc>>,
+
+    /// Time since which the mempool has been above the fill threshold
+    #[serde(skip)]
+    pub(super) mempool_full_since: Option<DateTime<Utc>>,
+
+    /// Last time the signing state of the node's validator key advanced
+    #[serde(skip)]
+    pub(super) priv_validator_advanced_at: Option<DateTime<Utc>>,
+
+    /// Last time each critical log pattern was matched
+    #[serde(skip)]
+    pub(super) critical_logs_at: Map<String, DateTime<Utc>>,
+
+    /// Alerts which are currently raised for this node
+    #[serde(skip)]
+    alerts: BTreeSet<String>,
+}
+
+impl Node {
+    /// Update whether the given alert condition is active for this node,
+    /// returning `true` if it was newly raised (i.e. needs to be paged)
+    pub fn alert(&mut self, name: &str, active: bool) -> bool {
+        if active {
+            self.alerts.insert(name.to_owned())
+        } else {
+            self.alerts.remove(name);
+            false
+        }
+    }
+}
+
+impl<'a> From<&'a tendermint::node::Info> for Node {
+    fn from(node_info: &'a tendermint::node::Info) -> Node {
+        Node {
+            id: node_info.id,
+            moniker: node_info.moniker.clone(),
+            peers: vec![],
+            anomalous_peers: vec![],
+            send_rate: 0,
+            recv_rate: 0,
+            disconnected_peers: Map::new(),
+            chain: None,
+            height_lag: None,
+            sync_progress: None,
+            validator: None,
+            data: None,
+            consensus: None,
+            mempool: None,
+            priv_validator: None,
+            keys: None,
+            settings: None,
+            upgrade: None,
+            metrics: vec![],
+            commands: Map::new(),
+            log_events: vec![],
+            failing_checks: Map::new(),
+            last_report_at: None,
+            mempool_full_since: None,
+            priv_validator_advanced_at: None,
+            critical_logs_at: Map::new(),
+            alerts: BTreeSet::new(),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Height lag of nodes against reference RPC endpoints
+
+use serde::Serialize;
+use tendermint::Time;
+
+/// Best height among a network's reference RPC endpoints
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ReferenceHeight {
+    /// Endpoint which reported the height
+    pub endpoint: String,
+
+    /// Latest block height
+    pub height: u64,
+
+    /// Time of the latest block
+    pub time: Time,
+}
+
+/// How far a node is behind the reference height
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct HeightLag {
+    /// Number of blocks behind (zero if ahead)
+    pub blocks: u64,
+
+    /// Seconds between the latest blocks (zero if ahead)
+    pub secs: u64,
+}
+
+impl HeightLag {
+    /// Compute the lag of a node at the given height and block time
+    pub fn new(reference: &ReferenceHeight, height: u64, time: Time) -> Self {
+        Self {
+            blocks: reference.height.saturating_sub(height),
+            secs: reference
+                .time
+                .duration_since(time)
+                .map(|lag| lag.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+}
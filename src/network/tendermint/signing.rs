@@ -0,0 +1,74 @@
+//! Rolling window of validator signatures
+
+use crate::{monitor::signing::SignatureState, prelude::*};
+use serde::Serialize;
+use tendermint::block;
+
+/// Rolling window of the configured validator's signatures on recent
+/// blocks, as reported by agents
+#[derive(Clone, Debug, Serialize)]
+pub struct SigningWindow {
+    /// Maximum number of blocks in the window
+    size: usize,
+
+    /// Signature state for each height in the window
+    signatures: Map<block::Height, SignatureState>,
+}
+
+impl SigningWindow {
+    /// Create a new signing window of the given size
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            signatures: Map::new(),
+        }
+    }
+
+    /// Record a signature, evicting the oldest ones which no longer fit in
+    /// the window
+    pub fn record(&mut self, height: block::Height, state: SignatureState) {
+        self.signatures.insert(height, state);
+
+        while self.signatures.len() > self.size {
+            let oldest = *self.signatures.keys().next().unwrap();
+            self.signatures.remove(&oldest);
+        }
+    }
+
+    /// Number of blocks missed within the window
+    pub fn missed_blocks(&self) -> usize {
+        self.signatures
+            .values()
+            .filter(|state| state.is_missed())
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_oldest_signatures() {
+        let mut window = SigningWindow::new(3);
+
+        for height in 1u32..=5 {
+            let state = if height % 2 == 0 {
+                SignatureState::Absent
+            } else {
+                SignatureState::Signed
+            };
+
+            window.record(block::Height::from(height), state);
+        }
+
+        assert_eq!(
+            window.signatures.keys().copied().collect::<Vec<_>>(),
+            [3u32, 4, 5]
+                .iter()
+                .map(|&h| block::Height::from(h))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(window.missed_blocks(), 1);
+    }
+}
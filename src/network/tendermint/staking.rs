@@ -0,0 +1,55 @@
+//! Cosmos SDK staking status of a network's validator
+
+use serde::Serialize;
+
+/// Staking and slashing status of a validator
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ValidatorStatus {
+    /// Validator operator address
+    pub operator_address: String,
+
+    /// Validator consensus address (if known)
+    pub consensus_address: Option<String>,
+
+    /// Bonding status
+    pub status: BondStatus,
+
+    /// Is the validator jailed?
+    pub jailed: bool,
+
+    /// Is the validator tombstoned (i.e. permanently jailed for
+    /// double-signing)? `None` if the signing info couldn't be queried.
+    pub tombstoned: Option<bool>,
+
+    /// Voting power in the active validator set (zero if not in it)
+    pub voting_power: u64,
+
+    /// Bonded tokens
+    pub tokens: String,
+
+    /// Commission rate (e.g. `0.05`)
+    pub commission_rate: Option<String>,
+
+    /// Blocks missed within the slashing window (if known)
+    pub missed_blocks_counter: Option<i64>,
+}
+
+/// Bonding status of a validator
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum BondStatus {
+    /// Status isn't specified
+    #[serde(rename = "unspecified")]
+    Unspecified,
+
+    /// Not in the active set
+    #[serde(rename = "unbonded")]
+    Unbonded,
+
+    /// Leaving the active set
+    #[serde(rename = "unbonding")]
+    Unbonding,
+
+    /// In the active set
+    #[serde(rename = "bonded")]
+    Bonded,
+}
@@ -0,0 +1,132 @@
+//! Catch-up progress of syncing nodes
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Weight of the latest sample in the smoothed sync rate
+const SMOOTHING: f64 = 0.2;
+
+/// Progress of a node which is catching up with the network
+#[derive(Clone, Debug, Serialize)]
+pub struct SyncProgress {
+    /// Latest height of the node
+    pub height: u64,
+
+    /// Best known height of the network
+    pub target_height: u64,
+
+    /// Blocks left to sync
+    pub remaining_blocks: u64,
+
+    /// Smoothed rate at which the node is syncing, in blocks per second
+    pub blocks_per_sec: f64,
+
+    /// Is the node syncing faster than the network produces blocks?
+    pub converging: bool,
+
+    /// Estimated seconds until the node has caught up (if it's converging)
+    pub eta_secs: Option<u64>,
+
+    /// Time of the last sample the rate was computed from
+    #[serde(skip)]
+    sampled_at: DateTime<Utc>,
+
+    /// Has the rate been measured yet (i.e. is there more than one sample)?
+    #[serde(skip)]
+    rate_measured: bool,
+}
+
+impl SyncProgress {
+    /// Update the progress with a new height sample.
+    ///
+    /// `chain_blocks_per_sec` is the rate at which the network produces
+    /// blocks, if known.
+    pub fn update(
+        last: Option<&SyncProgress>,
+        height: u64,
+        target_height: u64,
+        chain_blocks_per_sec: Option<f64>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let (blocks_per_sec, rate_measured) = match last {
+            Some(last) if now > last.sampled_at && height >= last.height => {
+                let elapsed = (now - last.sampled_at).num_milliseconds() as f64 / 1000.0;
+                let rate = (height - last.height) as f64 / elapsed;
+
+                // Seed the smoothed rate with the first measurement
+                let blocks_per_sec = if last.rate_measured {
+                    last.blocks_per_sec * (1.0 - SMOOTHING) + rate * SMOOTHING
+                } else {
+                    rate
+                };
+
+                (blocks_per_sec, true)
+            }
+            Some(last) => (last.blocks_per_sec, last.rate_measured),
+            None => (0.0, false),
+        };
+
+        let target_height = target_height.max(height);
+        let remaining_blocks = target_height - height;
+        let convergence_rate = blocks_per_sec - chain_blocks_per_sec.unwrap_or_default();
+        let converging = convergence_rate > 0.0;
+
+        let eta_secs = if converging {
+            Some((remaining_blocks as f64 / convergence_rate).ceil() as u64)
+        } else {
+            None
+        };
+
+        Self {
+            height,
+            target_height,
+            remaining_blocks,
+            blocks_per_sec,
+            converging,
+            eta_secs,
+            sampled_at: now,
+            rate_measured,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn estimates_catch_up_time() {
+        let start = Utc::now();
+        let first = SyncProgress::update(None, 1000, 2000, Some(0.5), start);
+        assert!(!first.converging);
+        assert_eq!(first.eta_secs, None);
+
+        let second = SyncProgress::update(
+            Some(&first),
+            1100,
+            2000,
+            Some(0.5),
+            start + Duration::seconds(10),
+        );
+
+        // 10 blocks/sec, 9.5 blocks/sec faster than the network
+        assert_eq!(second.remaining_blocks, 900);
+        assert_eq!(second.blocks_per_sec, 10.0);
+        assert!(second.converging);
+        assert_eq!(second.eta_secs, Some(95));
+
+        let third = SyncProgress::update(
+            Some(&second),
+            1150,
+            2000,
+            Some(0.5),
+            start + Duration::seconds(20),
+        );
+
+        // 5 blocks/sec smoothed to 9 blocks/sec
+        assert_eq!(third.remaining_blocks, 850);
+        assert!((third.blocks_per_sec - 9.0).abs() < 1e-9);
+        assert_eq!(third.eta_secs, Some(100));
+    }
+}
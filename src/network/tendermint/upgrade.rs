@@ -0,0 +1,20 @@
+//! Software upgrades of a network
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Scheduled software upgrade
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct UpgradePlan {
+    /// Name of the upgrade
+    pub name: String,
+
+    /// Height at which the upgrade happens
+    pub height: u64,
+
+    /// Additional information about the upgrade (e.g. binary URLs)
+    pub info: String,
+
+    /// Estimated time of the upgrade based on recent block times
+    pub estimated_time: Option<DateTime<Utc>>,
+}